@@ -0,0 +1,338 @@
+use crate::backend::{DirEntry, EntryKind, Metadata, Storage};
+use flate2::read::{DeflateDecoder, GzDecoder};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, Error, ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use zip::{CompressionMethod, ZipArchive};
+
+/// Archive formats `ArchiveStorage` can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Detects the format from the file name: `.tar`, `.tar.gz`/`.tgz` or `.zip`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the bytes of an archived file can be found.
+#[derive(Debug)]
+enum Location {
+    /// Offset of a tar member's data in the (decompressed) tar stream.
+    Range { offset: u64 },
+    /// Offset and compressed size of a zip member's data, and whether it is deflated rather
+    /// than stored.
+    Member {
+        offset: u64,
+        compressed_size: u64,
+        deflated: bool,
+    },
+}
+
+#[derive(Debug)]
+enum Node {
+    File { size: u64, location: Location },
+    Dir,
+}
+
+/// Read-only storage over the contents of a `.tar`, `.tar.gz` or `.zip` archive, without
+/// extracting it.
+///
+/// The archive is indexed when opened, recording only names, sizes and positions. Members are
+/// read from the archive on demand, each reader opening the archive file again; as a gzip stream
+/// cannot be seeked, opening a member of a gzipped tarball decompresses the archive again up to
+/// that member.
+///
+/// When the archive holds a single top-level directory and no storage root marker at its top
+/// level (as produced by `tar -cf root.tar root`), that directory becomes the storage's base.
+#[derive(Debug)]
+pub struct ArchiveStorage {
+    path: PathBuf,
+    format: ArchiveFormat,
+    nodes: BTreeMap<String, Node>,
+    base: String,
+}
+
+impl ArchiveStorage {
+    /// Opens and indexes the archive at `path`, detecting its format from the file name.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let format = ArchiveFormat::from_path(path).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} is not a .tar, .tar.gz or .zip archive", path.display()),
+            )
+        })?;
+
+        let mut storage = Self {
+            path: path.to_path_buf(),
+            format,
+            nodes: BTreeMap::new(),
+            base: String::new(),
+        };
+        storage.nodes.insert(String::new(), Node::Dir);
+
+        match format {
+            ArchiveFormat::Tar => storage.index_tar(File::open(path)?)?,
+            ArchiveFormat::TarGz => storage.index_tar(GzDecoder::new(File::open(path)?))?,
+            ArchiveFormat::Zip => storage.index_zip()?,
+        }
+        storage.base = storage.detect_base();
+
+        Ok(storage)
+    }
+
+    /// Format of the underlying archive.
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Narrows the storage to the directory `path` inside the archive.
+    pub fn subdir(mut self, path: &str) -> Result<Self, Error> {
+        let (name, node) = self.node(path)?;
+        if !matches!(node, Node::Dir) {
+            return Err(Error::new(
+                ErrorKind::NotADirectory,
+                format!("{path} is not a directory"),
+            ));
+        }
+        self.base = name;
+        Ok(self)
+    }
+
+    fn index_tar<R: Read>(&mut self, reader: R) -> Result<(), Error> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let name = normalize(&entry.path()?.to_string_lossy());
+            if name.is_empty() {
+                continue;
+            }
+
+            let entry_type = entry.header().entry_type();
+            if entry_type.is_dir() {
+                self.insert_dir(&name);
+            } else if entry_type.is_file() {
+                let location = Location::Range {
+                    offset: entry.raw_file_position(),
+                };
+                self.insert_file(&name, entry.size(), location);
+            }
+        }
+        Ok(())
+    }
+
+    fn index_zip(&mut self) -> Result<(), Error> {
+        let file = BufReader::new(File::open(&self.path)?);
+        let mut archive = ZipArchive::new(file).map_err(zip_error)?;
+        for i in 0..archive.len() {
+            let member = archive.by_index(i).map_err(zip_error)?;
+            let name = normalize(member.name());
+            if name.is_empty() {
+                continue;
+            }
+            if member.is_dir() {
+                self.insert_dir(&name);
+            } else {
+                let deflated = match member.compression() {
+                    CompressionMethod::Stored => false,
+                    CompressionMethod::Deflated => true,
+                    method => {
+                        return Err(Error::new(
+                            ErrorKind::Unsupported,
+                            format!("{} is compressed with unsupported {method}", member.name()),
+                        ));
+                    }
+                };
+                let location = Location::Member {
+                    offset: member.data_start(),
+                    compressed_size: member.compressed_size(),
+                    deflated,
+                };
+                self.insert_file(&name, member.size(), location);
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_dir(&mut self, name: &str) {
+        self.insert_parents(name);
+        self.nodes.insert(name.to_string(), Node::Dir);
+    }
+
+    fn insert_file(&mut self, name: &str, size: u64, location: Location) {
+        self.insert_parents(name);
+        self.nodes
+            .insert(name.to_string(), Node::File { size, location });
+    }
+
+    /// Archives need not list directories, so every ancestor of a member is recorded.
+    fn insert_parents(&mut self, name: &str) {
+        let mut parent = name;
+        while let Some((dir, _)) = parent.rsplit_once('/') {
+            self.nodes.entry(dir.to_string()).or_insert(Node::Dir);
+            parent = dir;
+        }
+    }
+
+    fn detect_base(&self) -> String {
+        let top: Vec<(&String, &Node)> = self.children("").collect();
+        let has_marker = top.iter().any(|(name, _)| name.starts_with("0=ocfl_"));
+        match top.as_slice() {
+            [(name, Node::Dir)] if !has_marker => name.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Nodes directly inside the directory `dir` (an internal, base-relative name).
+    fn children<'a>(&'a self, dir: &str) -> Box<dyn Iterator<Item = (&'a String, &'a Node)> + 'a> {
+        if dir.is_empty() {
+            return Box::new(
+                self.nodes
+                    .iter()
+                    .filter(|(name, _)| !name.is_empty() && !name.contains('/')),
+            );
+        }
+
+        // Descendants of `dir` sort between "dir/" and "dir0", as '0' follows '/'.
+        let start = format!("{dir}/");
+        let end = format!("{dir}0");
+        let depth = start.len();
+        Box::new(
+            self.nodes
+                .range(start..end)
+                .filter(move |(name, _)| !name[depth..].contains('/')),
+        )
+    }
+
+    fn node_name(&self, path: &str) -> String {
+        let path = normalize(path);
+        match (self.base.is_empty(), path.is_empty()) {
+            (true, _) => path,
+            (false, true) => self.base.clone(),
+            (false, false) => format!("{}/{path}", self.base),
+        }
+    }
+
+    fn node(&self, path: &str) -> Result<(String, &Node), Error> {
+        let name = self.node_name(path);
+        match self.nodes.get(&name) {
+            Some(node) => Ok((name, node)),
+            None => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{path} not found in {}", self.path.display()),
+            )),
+        }
+    }
+}
+
+impl Storage for ArchiveStorage {
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, Error> {
+        let (dir, node) = self.node(path)?;
+        if !matches!(node, Node::Dir) {
+            return Err(Error::new(
+                ErrorKind::NotADirectory,
+                format!("{path} is not a directory"),
+            ));
+        }
+
+        Ok(self
+            .children(&dir)
+            .map(|(name, node)| DirEntry {
+                name: name.rsplit('/').next().unwrap_or(name).to_string(),
+                kind: match node {
+                    Node::Dir => EntryKind::Dir,
+                    Node::File { .. } => EntryKind::File,
+                },
+            })
+            .collect())
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + '_>, Error> {
+        let (_, node) = self.node(path)?;
+        let Node::File { size, location } = node else {
+            return Err(Error::new(
+                ErrorKind::IsADirectory,
+                format!("{path} is a directory"),
+            ));
+        };
+
+        match location {
+            Location::Range { offset } if self.format == ArchiveFormat::TarGz => {
+                let mut stream = GzDecoder::new(BufReader::new(File::open(&self.path)?));
+                io::copy(&mut (&mut stream).take(*offset), &mut io::sink())?;
+                Ok(Box::new(stream.take(*size)))
+            }
+            Location::Range { offset } => {
+                let mut file = File::open(&self.path)?;
+                file.seek(SeekFrom::Start(*offset))?;
+                Ok(Box::new(file.take(*size)))
+            }
+            Location::Member {
+                offset,
+                compressed_size,
+                deflated,
+            } => {
+                let mut file = File::open(&self.path)?;
+                file.seek(SeekFrom::Start(*offset))?;
+                let data = BufReader::new(file).take(*compressed_size);
+                if *deflated {
+                    Ok(Box::new(DeflateDecoder::new(data).take(*size)))
+                } else {
+                    Ok(Box::new(data.take(*size)))
+                }
+            }
+        }
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata, Error> {
+        let (_, node) = self.node(path)?;
+        Ok(match node {
+            Node::Dir => Metadata {
+                kind: EntryKind::Dir,
+                size: 0,
+            },
+            Node::File { size, .. } => Metadata {
+                kind: EntryKind::File,
+                size: *size,
+            },
+        })
+    }
+}
+
+/// Strips leading `./` and `/` and trailing `/` from an archive member name.
+fn normalize(name: &str) -> String {
+    let mut name = name.trim_matches('/');
+    while let Some(rest) = name.strip_prefix("./") {
+        name = rest.trim_start_matches('/');
+    }
+    if name == "." {
+        return String::new();
+    }
+    name.to_string()
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    match e {
+        zip::result::ZipError::Io(e) => e,
+        zip::result::ZipError::FileNotFound => Error::new(ErrorKind::NotFound, e.to_string()),
+        e => Error::new(ErrorKind::InvalidData, e.to_string()),
+    }
+}
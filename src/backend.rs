@@ -0,0 +1,178 @@
+use crate::archive::{ArchiveFormat, ArchiveStorage};
+use crate::s3::S3Storage;
+use std::{
+    fs::File,
+    io::{Error, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+/// Whether a storage entry is a file or a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Dir,
+}
+
+/// What `Storage::metadata` reports about an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub kind: EntryKind,
+    /// Size in bytes; zero for directories.
+    pub size: u64,
+}
+
+/// An entry returned by `Storage::read_dir`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub kind: EntryKind,
+}
+
+/// Read-only access to a tree of files, such as a local directory or an object store bucket.
+///
+/// Paths are relative to the storage's base, use `/` as separator and the empty string names
+/// the base itself.
+pub trait Storage {
+    /// Lists the entries of the directory at `path`.
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, Error>;
+
+    /// Opens the file at `path` for reading.
+    fn open(&self, path: &str) -> Result<Box<dyn Read + '_>, Error>;
+
+    /// Returns the kind and size of the entry at `path`, or a `NotFound` error.
+    fn metadata(&self, path: &str) -> Result<Metadata, Error>;
+
+    /// Reads the whole file at `path`.
+    fn read(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let mut contents = Vec::new();
+        self.open(path)?.read_to_end(&mut contents)?;
+        Ok(contents)
+    }
+
+    /// Reads the whole file at `path` as UTF-8 text.
+    fn read_to_string(&self, path: &str) -> Result<String, Error> {
+        String::from_utf8(self.read(path)?).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Returns true if `path` exists and is a file.
+    fn is_file(&self, path: &str) -> bool {
+        matches!(self.metadata(path), Ok(m) if m.kind == EntryKind::File)
+    }
+
+    /// Returns true if `path` exists and is a directory.
+    fn is_dir(&self, path: &str) -> bool {
+        matches!(self.metadata(path), Ok(m) if m.kind == EntryKind::Dir)
+    }
+}
+
+/// Joins `name` onto the storage path `base`.
+pub fn join_path(base: &str, name: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if base.is_empty() {
+        name.to_string()
+    } else {
+        format!("{base}/{name}")
+    }
+}
+
+/// Storage backed by a directory of the local filesystem.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Creates a storage whose base is the directory `root`.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        if path.is_empty() {
+            self.root.clone()
+        } else {
+            self.root.join(path)
+        }
+    }
+}
+
+impl Storage for LocalStorage {
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, Error> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(self.resolve(path))? {
+            let entry = entry?;
+            // Symlinks are listed as files and never followed, so a link cycle cannot trap a
+            // walk over the storage.
+            let kind = if entry.file_type()?.is_dir() {
+                EntryKind::Dir
+            } else {
+                EntryKind::File
+            };
+            entries.push(DirEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                kind,
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + '_>, Error> {
+        Ok(Box::new(File::open(self.resolve(path))?))
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata, Error> {
+        let metadata = std::fs::metadata(self.resolve(path))?;
+        Ok(Metadata {
+            kind: if metadata.is_dir() {
+                EntryKind::Dir
+            } else {
+                EntryKind::File
+            },
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+        })
+    }
+}
+
+/// Returns true if `location` names remote storage (an `s3://` URL) rather than a local path.
+pub fn is_remote(location: &str) -> bool {
+    location.starts_with("s3://")
+}
+
+/// Returns true if `location` names an existing `.tar`, `.tar.gz` or `.zip` archive file, or a
+/// path inside one such as `snapshot.tar/objects/abc`. Directories are never archives, whatever
+/// their name.
+pub fn is_archive(location: &str) -> bool {
+    split_archive_path(location).is_some()
+}
+
+/// Splits `location` into the archive file and the path inside it.
+fn split_archive_path(location: &str) -> Option<(&Path, String)> {
+    if is_remote(location) {
+        return None;
+    }
+    let path = Path::new(location);
+    path.ancestors()
+        .filter(|ancestor| ArchiveFormat::from_path(ancestor).is_some())
+        .find(|ancestor| ancestor.is_file())
+        .map(|archive| {
+            let inner = path.strip_prefix(archive).unwrap_or(Path::new(""));
+            (archive, inner.to_string_lossy().replace('\\', "/"))
+        })
+}
+
+/// Opens the storage named by `location`: an `s3://bucket/prefix` URL, a `.tar`, `.tar.gz` or
+/// `.zip` archive (optionally followed by a path inside it), or a local directory.
+///
+/// S3 endpoints and credentials are taken from the environment, see `S3Storage::from_url`.
+pub fn open_storage(location: &str) -> Result<Box<dyn Storage>, Error> {
+    if is_remote(location) {
+        Ok(Box::new(S3Storage::from_url(location)?))
+    } else if let Some((archive, inner)) = split_archive_path(location) {
+        Ok(Box::new(ArchiveStorage::open(archive)?.subdir(&inner)?))
+    } else {
+        Ok(Box::new(LocalStorage::new(location)))
+    }
+}
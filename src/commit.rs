@@ -0,0 +1,142 @@
+use crate::create::{VersionInfo, add_content, staging_path};
+use crate::diff::{VersionDiff, diff_states};
+use crate::inventory::{is_zero_padded, read_object_inventory, version_number, write_inventory};
+use crate::object::is_object_root;
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// The version written by `commit_version` and how it differs from the previous head.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub version: String,
+    pub diff: VersionDiff,
+}
+
+/// Adds a version to the OCFL object at `path` whose logical state is the files below
+/// `source`.
+///
+/// Content whose digest is already in the manifest is referenced rather than stored again.
+/// The version directory is built in a staging directory and renamed into place, after which
+/// the root sidecar and then the root inventory are replaced. Fails without writing anything if
+/// `source` matches the head state.
+///
+/// If the process dies between those two replacements, the root sidecar no longer matches the
+/// root inventory. The new version directory already holds the complete pair: copy its
+/// `inventory.json` and sidecar over the root ones to finish the commit.
+///
+/// Refuses to run while the object root holds a `.vN.staging-<pid>` directory: it belongs
+/// either to a commit still in progress or to one that died, and is left for the caller to
+/// remove.
+pub fn commit_version<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    source: Q,
+    info: &VersionInfo,
+) -> Result<Commit, Error> {
+    let p = path.as_ref();
+    let source = source.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+    if !source.is_dir() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a directory", source.display()),
+        ));
+    }
+
+    let mut inventory = read_object_inventory(p)?;
+    let head_state = inventory.version(None)?.state.clone();
+    let version = next_version(&inventory.head)?;
+    let version_dir = p.join(&version);
+    if version_dir.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", version_dir.display()),
+        ));
+    }
+
+    for entry in fs::read_dir(p)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if name.starts_with(".v") && name.contains(".staging-") {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} holds {name} from another commit", p.display()),
+            ));
+        }
+    }
+
+    let staging = staging_path(&version_dir);
+    let result = fs::create_dir(&staging).and_then(|_| {
+        let state = add_content(&mut inventory, &staging, &version, source)?;
+        let diff = diff_states(&head_state, &state);
+        if diff.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "nothing to commit: {} matches {}",
+                    source.display(),
+                    inventory.head
+                ),
+            ));
+        }
+
+        inventory
+            .versions
+            .insert(version.clone(), info.to_version(state));
+        inventory.head = version.clone();
+        write_inventory(&staging, &inventory)?;
+        fs::rename(&staging, &version_dir)?;
+        Ok(diff)
+    });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    let diff = result?;
+
+    // The new version directory holds the complete inventory; copy it over the root one file
+    // at a time, each via a rename so readers never see a partially written file. The sidecar
+    // goes first, so the new head only becomes visible with the last rename.
+    let sidecar = format!("inventory.json.{}", inventory.digest_algorithm);
+    for name in [sidecar.as_str(), "inventory.json"] {
+        let temporary = p.join(format!(".{name}.tmp"));
+        let result = fs::copy(version_dir.join(name), &temporary)
+            .and_then(|_| fs::rename(&temporary, p.join(name)));
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        result?;
+    }
+
+    Ok(Commit { version, diff })
+}
+
+/// Name of the version after `head`, keeping its zero-padding: "v2" -> "v3", "v009" -> "v010".
+fn next_version(head: &str) -> Result<String, Error> {
+    let number = version_number(head).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("head {head} is not a version name"),
+        )
+    })?;
+    let next = number + 1;
+    if is_zero_padded(head) {
+        let width = head.len() - 1;
+        let name = format!("v{next:0width$}");
+        if name.len() > head.len() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("zero-padded version sequence ends at {head}"),
+            ));
+        }
+        Ok(name)
+    } else {
+        Ok(format!("v{next}"))
+    }
+}
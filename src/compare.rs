@@ -0,0 +1,159 @@
+use crate::crawl::{ObjectRecord, object_records};
+use crate::fixity::{FixityIssue, check_fixity};
+use crate::inventory::{Inventory, read_object_inventory};
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// The copies of one object identifier found in both storage roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectPair {
+    pub a: ObjectRecord,
+    pub b: ObjectRecord,
+}
+
+impl ObjectPair {
+    /// Returns true if both copies have the same head version.
+    pub fn heads_match(&self) -> bool {
+        self.a.head == self.b.head
+    }
+
+    /// Returns true if both copies have readable, identical inventory sidecar digests.
+    pub fn inventories_match(&self) -> bool {
+        self.a.inventory_digest.is_some() && self.a.inventory_digest == self.b.inventory_digest
+    }
+}
+
+/// A content path that the two copies of an object record under different digests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDifference {
+    /// Content path relative to the object root.
+    pub path: String,
+    pub algorithm: String,
+    pub a: String,
+    pub b: String,
+}
+
+/// Objects of two storage roots matched by identifier.
+#[derive(Debug, Default)]
+pub struct Comparison {
+    /// Objects only present in the second storage root.
+    pub missing_in_a: Vec<ObjectRecord>,
+    /// Objects only present in the first storage root.
+    pub missing_in_b: Vec<ObjectRecord>,
+    pub pairs: Vec<ObjectPair>,
+    pub errors: Vec<Error>,
+}
+
+impl Comparison {
+    /// Returns true if both roots hold the same objects with identical inventories.
+    pub fn is_consistent(&self) -> bool {
+        self.missing_in_a.is_empty()
+            && self.missing_in_b.is_empty()
+            && self.pairs.iter().all(ObjectPair::inventories_match)
+    }
+}
+
+/// Crawls storage roots `a` and `b` and matches their objects by identifier.
+pub fn compare_roots<P: AsRef<Path>, Q: AsRef<Path>>(a: P, b: Q) -> Comparison {
+    let mut comparison = Comparison::default();
+    let mut side_a = by_id(a, &mut comparison.errors);
+    let side_b = by_id(b, &mut comparison.errors);
+
+    for (id, b) in side_b {
+        match side_a.remove(&id) {
+            Some(a) => comparison.pairs.push(ObjectPair { a, b }),
+            None => comparison.missing_in_a.push(b),
+        }
+    }
+    comparison.missing_in_b = side_a.into_values().collect();
+    comparison.pairs.sort_by(|x, y| x.a.id.cmp(&y.a.id));
+
+    comparison
+}
+
+/// Runs a fixity check on both copies of `pair`, returning the issues found on each side, or
+/// the error that kept that side from being checked.
+pub fn compare_fixity(
+    pair: &ObjectPair,
+) -> (
+    Result<Vec<FixityIssue>, Error>,
+    Result<Vec<FixityIssue>, Error>,
+) {
+    (check_fixity(&pair.a.path), check_fixity(&pair.b.path))
+}
+
+/// Compares the digests both copies of `pair` record for each content path they share, using
+/// the manifest and `fixity` algorithms the two inventories have in common.
+///
+/// Unlike `compare_fixity`, this catches copies that are each intact but hold different content.
+pub fn compare_content(pair: &ObjectPair) -> Result<Vec<ContentDifference>, Error> {
+    let (inventory_a, inventory_b) = (
+        read_object_inventory(&pair.a.path)?,
+        read_object_inventory(&pair.b.path)?,
+    );
+    let a = content_digests(&inventory_a);
+    let b = content_digests(&inventory_b);
+
+    let mut differences = Vec::new();
+    for (path, digests_a) in &a {
+        let Some(digests_b) = b.get(path) else {
+            continue;
+        };
+        differences.extend(digests_a.iter().find_map(|(algorithm, digest_a)| {
+            let digest_b = digests_b.get(algorithm)?;
+            (digest_a != digest_b).then(|| ContentDifference {
+                path: path.to_string(),
+                algorithm: algorithm.to_string(),
+                a: digest_a.clone(),
+                b: digest_b.clone(),
+            })
+        }));
+    }
+    Ok(differences)
+}
+
+/// Digests recorded for each content path of `inventory`, by algorithm.
+fn content_digests(inventory: &Inventory) -> BTreeMap<&str, BTreeMap<&str, String>> {
+    let mut digests: BTreeMap<&str, BTreeMap<&str, String>> = BTreeMap::new();
+    let blocks = inventory
+        .fixity
+        .iter()
+        .chain([(&inventory.digest_algorithm, &inventory.manifest)]);
+    for (algorithm, block) in blocks {
+        for (digest, content_paths) in block {
+            for content_path in content_paths {
+                digests
+                    .entry(content_path)
+                    .or_default()
+                    .insert(algorithm, digest.to_lowercase());
+            }
+        }
+    }
+    digests
+}
+
+fn by_id<P: AsRef<Path>>(path: P, errors: &mut Vec<Error>) -> BTreeMap<String, ObjectRecord> {
+    let (records, crawl_errors) = object_records(path);
+    errors.extend(crawl_errors);
+
+    let mut map = BTreeMap::new();
+    for record in records {
+        if let Some(existing) = map.get(&record.id) {
+            let existing: &ObjectRecord = existing;
+            errors.push(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} has the same id as {}; only the first is compared",
+                    record.path.display(),
+                    existing.path.display()
+                ),
+            ));
+            continue;
+        }
+        map.insert(record.id.clone(), record);
+    }
+    map
+}
@@ -0,0 +1,110 @@
+use crate::backend::{EntryKind, LocalStorage, Storage, join_path};
+use crate::inventory::{read_object_inventory, read_sidecar_digest};
+use crate::object::is_object_root_in;
+use std::{
+    io::Error,
+    path::{Path, PathBuf},
+};
+
+/// Object roots found below a storage root, along with the errors met while walking it.
+#[derive(Debug, Default)]
+pub struct Crawl {
+    pub objects: Vec<PathBuf>,
+    pub errors: Vec<Error>,
+}
+
+/// Identifying details of a crawled object, read from its root inventory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectRecord {
+    pub path: PathBuf,
+    pub id: String,
+    pub head: String,
+    /// Digest from the root inventory sidecar, if it could be read.
+    pub inventory_digest: Option<String>,
+}
+
+/// Walks the storage root at `path` and collects every OCFL object root below it.
+///
+/// Object roots are not descended into and symlinks are not followed. Unreadable directories
+/// are recorded in `Crawl::errors` and skipped rather than aborting the walk. Objects are
+/// returned in sorted order.
+pub fn crawl<P: AsRef<Path>>(path: P) -> Crawl {
+    let root = path.as_ref();
+    let mut result = walk(&LocalStorage::new(root), "", &|dir| {
+        root.join(dir).display().to_string()
+    });
+    result.objects = result
+        .objects
+        .into_iter()
+        .map(|object| root.join(object))
+        .collect();
+    result
+}
+
+/// Like `crawl`, for the storage root at `path` within `storage`. Objects are returned as
+/// storage paths, in sorted order.
+pub fn crawl_in(storage: &dyn Storage, path: &str) -> Crawl {
+    walk(storage, path, &|dir| dir.to_string())
+}
+
+/// Walks `storage` from `path`; `location` names a directory in error messages.
+fn walk(storage: &dyn Storage, path: &str, location: &dyn Fn(&str) -> String) -> Crawl {
+    let mut result = Crawl::default();
+    let mut pending = vec![path.to_string()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = match storage.read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                result
+                    .errors
+                    .push(Error::new(e.kind(), format!("{}: {e}", location(&dir))));
+                continue;
+            }
+        };
+        // Push in reverse so directories are visited in name order.
+        for entry in entries.into_iter().rev() {
+            if entry.kind != EntryKind::Dir {
+                continue;
+            }
+            let child = join_path(&dir, &entry.name);
+            if is_object_root_in(storage, &child) {
+                result.objects.push(PathBuf::from(&child));
+            } else {
+                pending.push(child);
+            }
+        }
+    }
+
+    result.objects.sort();
+    result
+}
+
+/// Crawls the storage root at `path` and reads an `ObjectRecord` for every object found.
+///
+/// Objects whose inventory cannot be read are reported as errors alongside walk errors.
+pub fn object_records<P: AsRef<Path>>(path: P) -> (Vec<ObjectRecord>, Vec<Error>) {
+    let result = crawl(path);
+    let mut records = Vec::new();
+    let mut errors = result.errors;
+
+    for object in result.objects {
+        match read_object_inventory(&object) {
+            Ok(inventory) => {
+                let inventory_digest = inventory
+                    .algorithm()
+                    .and_then(|algorithm| read_sidecar_digest(&object, algorithm))
+                    .ok();
+                records.push(ObjectRecord {
+                    path: object,
+                    id: inventory.id,
+                    head: inventory.head,
+                    inventory_digest,
+                });
+            }
+            Err(e) => errors.push(Error::new(e.kind(), format!("{}: {e}", object.display()))),
+        }
+    }
+
+    (records, errors)
+}
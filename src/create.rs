@@ -0,0 +1,202 @@
+use crate::digest::{DigestAlgorithm, file_digest};
+use crate::inventory::{DigestMap, Inventory, User, Version, write_inventory};
+use crate::layout::Layout;
+use crate::storage::is_storage_root;
+use chrono::{SecondsFormat, Utc};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// OCFL version written by `create_object`.
+pub const SPEC_VERSION: &str = "1.1";
+
+/// Metadata recorded in the inventory for a newly written version.
+#[derive(Debug, Clone, Default)]
+pub struct VersionInfo {
+    pub message: Option<String>,
+    pub user: Option<User>,
+    /// RFC 3339 timestamp; the current time if `None`.
+    pub created: Option<String>,
+}
+
+impl VersionInfo {
+    pub(crate) fn to_version(&self, state: DigestMap) -> Version {
+        Version {
+            created: self
+                .created
+                .clone()
+                .unwrap_or_else(|| Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+            message: self.message.clone(),
+            user: self.user.clone(),
+            state,
+        }
+    }
+}
+
+/// Creates an OCFL 1.1 object with identifier `id` in the storage root at `root`, whose `v1`
+/// holds the files below `source`. Returns the new object root.
+///
+/// The object is placed at the path the storage root's layout computes for `id`. It is built
+/// in a staging directory next to that path and renamed into place once complete, so a failed
+/// create leaves no partial object behind. `digest_algorithm` must be sha512 or sha256.
+pub fn create_object<P: AsRef<Path>, Q: AsRef<Path>>(
+    root: P,
+    id: &str,
+    source: Q,
+    digest_algorithm: DigestAlgorithm,
+    info: &VersionInfo,
+) -> Result<PathBuf, Error> {
+    let root = root.as_ref();
+    let source = source.as_ref();
+
+    if !is_storage_root(root) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL storage root",
+        ));
+    }
+    if !source.is_dir() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a directory", source.display()),
+        ));
+    }
+    digest_algorithm.require_core()?;
+
+    let layout = Layout::from_storage_root(root)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            "storage root does not declare a layout in ocfl_layout.json",
+        )
+    })?;
+    let target = root.join(layout.object_path(id)?);
+    if target.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", target.display()),
+        ));
+    }
+
+    let staging = staging_path(&target);
+    let parent = staging.parent().unwrap_or(root);
+    fs::create_dir_all(parent)?;
+    let result = write_object(&staging, id, source, digest_algorithm, info)
+        .and_then(|_| fs::rename(&staging, &target));
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&staging);
+    }
+    result.map(|_| target)
+}
+
+fn write_object(
+    dir: &Path,
+    id: &str,
+    source: &Path,
+    digest_algorithm: DigestAlgorithm,
+    info: &VersionInfo,
+) -> Result<(), Error> {
+    fs::create_dir(dir)?;
+    fs::write(
+        dir.join(format!("0=ocfl_object_{SPEC_VERSION}")),
+        format!("ocfl_object_{SPEC_VERSION}\n"),
+    )?;
+
+    let mut inventory = Inventory {
+        id: id.to_string(),
+        type_: format!("https://ocfl.io/{SPEC_VERSION}/spec/#inventory"),
+        digest_algorithm: digest_algorithm.to_string(),
+        head: String::from("v1"),
+        content_directory: None,
+        manifest: DigestMap::new(),
+        versions: BTreeMap::new(),
+        fixity: BTreeMap::new(),
+    };
+
+    let version_dir = dir.join("v1");
+    fs::create_dir(&version_dir)?;
+    let state = add_content(&mut inventory, &version_dir, "v1", source)?;
+    inventory
+        .versions
+        .insert(String::from("v1"), info.to_version(state));
+
+    write_inventory(&version_dir, &inventory)?;
+    write_inventory(dir, &inventory)
+}
+
+/// Adds the files below `source` to `version`, whose directory is being written at
+/// `version_dir`: content not yet in the manifest is copied to the version's content directory
+/// and recorded there. Returns the version's state.
+pub(crate) fn add_content(
+    inventory: &mut Inventory,
+    version_dir: &Path,
+    version: &str,
+    source: &Path,
+) -> Result<DigestMap, Error> {
+    let algorithm = inventory.algorithm()?.require_core()?;
+    let content_directory = inventory.content_directory().to_string();
+    let mut state = DigestMap::new();
+
+    for (logical_path, file) in source_files(source)? {
+        let digest = file_digest(algorithm, &file)?;
+        state
+            .entry(digest.clone())
+            .or_default()
+            .push(logical_path.clone());
+        if inventory.content_path(&digest).is_some() {
+            continue;
+        }
+
+        let target = version_dir.join(&content_directory).join(&logical_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&file, &target)?;
+        inventory.manifest.insert(
+            digest,
+            vec![format!("{version}/{content_directory}/{logical_path}")],
+        );
+    }
+
+    Ok(state)
+}
+
+/// Files below `source` paired with their `/`-separated logical paths, in path order.
+fn source_files(source: &Path) -> Result<Vec<(String, PathBuf)>, Error> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(source).follow_links(true).sort_by_file_name() {
+        let entry = entry.map_err(Error::other)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .expect("walked paths are below the source");
+        let parts = relative
+            .components()
+            .map(|c| {
+                c.as_os_str().to_str().ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("{} is not valid UTF-8", entry.path().display()),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        files.push((parts.join("/"), entry.into_path()));
+    }
+    Ok(files)
+}
+
+/// A sibling of `target` to build into before renaming it into place.
+pub(crate) fn staging_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{name}.staging-{}", std::process::id()))
+}
@@ -0,0 +1,211 @@
+use crate::crawl::crawl;
+use crate::digest::DigestAlgorithm;
+use crate::inventory::{Inventory, content_file_path, read_object_inventory};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Error,
+    path::{Path, PathBuf},
+};
+
+/// Algorithms preferred when naming a piece of content, strongest first.
+const PREFERRED_ALGORITHMS: [DigestAlgorithm; 2] =
+    [DigestAlgorithm::Sha512, DigestAlgorithm::Sha256];
+
+/// A piece of content stored by more than one object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedContent {
+    pub algorithm: String,
+    pub digest: String,
+    pub size: u64,
+    /// Number of stored copies across all objects.
+    pub copies: usize,
+    pub objects: Vec<PathBuf>,
+}
+
+impl SharedContent {
+    /// Bytes that would be saved by storing this content once.
+    pub fn duplicated_bytes(&self) -> u64 {
+        self.size * (self.copies as u64 - 1)
+    }
+}
+
+/// Content deduplication across the objects of one or more storage roots.
+#[derive(Debug, Default)]
+pub struct DedupReport {
+    pub objects: usize,
+    pub unique_contents: usize,
+    /// Bytes of all stored content files.
+    pub total_bytes: u64,
+    /// Bytes needed if every distinct content were stored once.
+    pub unique_bytes: u64,
+    /// Content shared by two or more objects, most duplicated bytes first.
+    pub shared: Vec<SharedContent>,
+    pub errors: Vec<Error>,
+}
+
+impl DedupReport {
+    /// Bytes stored more than once.
+    pub fn duplicated_bytes(&self) -> u64 {
+        self.total_bytes - self.unique_bytes
+    }
+}
+
+/// One stored content file and every digest known for it.
+struct ContentFile {
+    object: usize,
+    size: u64,
+    digests: Vec<(String, String)>,
+}
+
+/// Aggregates manifest digests across every object under `paths` and reports duplicated content.
+///
+/// Objects using different digest algorithms are matched through their `fixity` blocks: two
+/// content files are the same content if they share a digest under any algorithm.
+pub fn analyze_dedup<P: AsRef<Path>>(paths: &[P]) -> DedupReport {
+    let mut report = DedupReport::default();
+    let mut objects = Vec::new();
+    let mut files = Vec::new();
+
+    for path in paths {
+        let result = crawl(path);
+        report.errors.extend(result.errors);
+
+        for object in result.objects {
+            match read_object_inventory(&object) {
+                Ok(inventory) => {
+                    files.extend(content_files(&object, objects.len(), &inventory));
+                    objects.push(object);
+                }
+                Err(e) => report
+                    .errors
+                    .push(Error::new(e.kind(), format!("{}: {e}", object.display()))),
+            }
+        }
+    }
+    report.objects = objects.len();
+
+    // Union content files that share any (algorithm, digest) pair.
+    let mut sets = DisjointSet::new(files.len());
+    let mut seen: HashMap<&(String, String), usize> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        for key in &file.digests {
+            match seen.get(key) {
+                Some(&other) => sets.union(index, other),
+                None => {
+                    seen.insert(key, index);
+                }
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..files.len() {
+        groups.entry(sets.find(index)).or_default().push(index);
+    }
+
+    report.unique_contents = groups.len();
+    for members in groups.values() {
+        // A missing content file counts as empty, so take the size of the largest copy.
+        let size = members.iter().map(|&i| files[i].size).max().unwrap_or(0);
+        report.unique_bytes += size;
+        report.total_bytes += members.iter().map(|&i| files[i].size).sum::<u64>();
+
+        let owners: BTreeSet<usize> = members.iter().map(|&i| files[i].object).collect();
+        if owners.len() < 2 {
+            continue;
+        }
+
+        let (algorithm, digest) = preferred_digest(members.iter().map(|&i| &files[i]));
+        report.shared.push(SharedContent {
+            algorithm,
+            digest,
+            size,
+            copies: members.len(),
+            objects: owners.iter().map(|&o| objects[o].clone()).collect(),
+        });
+    }
+
+    report.shared.sort_by(|a, b| {
+        b.duplicated_bytes()
+            .cmp(&a.duplicated_bytes())
+            .then_with(|| a.digest.cmp(&b.digest))
+    });
+
+    report
+}
+
+fn content_files(path: &Path, object: usize, inventory: &Inventory) -> Vec<ContentFile> {
+    let mut by_content_path: HashMap<&str, Vec<(String, String)>> = HashMap::new();
+
+    for (digest, content_paths) in &inventory.manifest {
+        for content_path in content_paths {
+            by_content_path
+                .entry(content_path)
+                .or_default()
+                .push((inventory.digest_algorithm.clone(), digest.to_lowercase()));
+        }
+    }
+    for (algorithm, block) in &inventory.fixity {
+        // Equal sizes do not make equal content.
+        if matches!(algorithm.parse(), Ok(DigestAlgorithm::Size)) {
+            continue;
+        }
+        for (digest, content_paths) in block {
+            for content_path in content_paths {
+                if let Some(digests) = by_content_path.get_mut(content_path.as_str()) {
+                    digests.push((algorithm.clone(), digest.to_lowercase()));
+                }
+            }
+        }
+    }
+
+    by_content_path
+        .into_iter()
+        .map(|(content_path, digests)| ContentFile {
+            object,
+            size: content_file_path(path, content_path)
+                .and_then(std::fs::metadata)
+                .map(|m| m.len())
+                .unwrap_or(0),
+            digests,
+        })
+        .collect()
+}
+
+fn preferred_digest<'a>(files: impl Iterator<Item = &'a ContentFile>) -> (String, String) {
+    let digests: Vec<&(String, String)> = files.flat_map(|f| &f.digests).collect();
+    PREFERRED_ALGORITHMS
+        .iter()
+        .find_map(|preferred| digests.iter().find(|(alg, _)| alg == preferred.name()))
+        .or_else(|| digests.first())
+        .map(|(alg, digest)| (alg.clone(), digest.clone()))
+        .unwrap_or_default()
+}
+
+/// Minimal union-find used to group equivalent content files.
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, mut index: usize) -> usize {
+        while self.parent[index] != index {
+            self.parent[index] = self.parent[self.parent[index]];
+            index = self.parent[index];
+        }
+        index
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[a] = b;
+        }
+    }
+}
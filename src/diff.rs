@@ -0,0 +1,132 @@
+use crate::inventory::{DigestMap, read_object_inventory};
+use crate::object::is_object_root;
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// A logical file that was added or removed between two versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub digest: String,
+}
+
+/// A logical file whose content digest changed between two versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modification {
+    pub path: String,
+    pub old_digest: String,
+    pub new_digest: String,
+}
+
+/// A logical file that moved to a new path with unchanged content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+    pub digest: String,
+}
+
+/// Changes between the logical states of two versions, each list sorted by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionDiff {
+    pub added: Vec<FileChange>,
+    pub removed: Vec<FileChange>,
+    pub modified: Vec<Modification>,
+    pub renamed: Vec<Rename>,
+}
+
+impl VersionDiff {
+    /// Returns true if the two states are identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.renamed.is_empty()
+    }
+}
+
+/// Compares two version `state` blocks.
+///
+/// A path that disappears while another path with the same digest appears is reported as a
+/// rename rather than as a removal and an addition.
+pub fn diff_states(old: &DigestMap, new: &DigestMap) -> VersionDiff {
+    let old_paths = by_path(old);
+    let new_paths = by_path(new);
+    let mut diff = VersionDiff::default();
+
+    let mut removed: Vec<(&str, &str)> = Vec::new();
+    for (path, old_digest) in &old_paths {
+        match new_paths.get(path) {
+            None => removed.push((path, old_digest)),
+            Some(new_digest) if new_digest != old_digest => diff.modified.push(Modification {
+                path: path.to_string(),
+                old_digest: old_digest.to_string(),
+                new_digest: new_digest.to_string(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (path, digest) in &new_paths {
+        if old_paths.contains_key(path) {
+            continue;
+        }
+        match removed.iter().position(|(_, d)| d == digest) {
+            Some(index) => {
+                let (from, _) = removed.remove(index);
+                diff.renamed.push(Rename {
+                    from: from.to_string(),
+                    to: path.to_string(),
+                    digest: digest.to_string(),
+                });
+            }
+            None => diff.added.push(FileChange {
+                path: path.to_string(),
+                digest: digest.to_string(),
+            }),
+        }
+    }
+
+    diff.removed = removed
+        .into_iter()
+        .map(|(path, digest)| FileChange {
+            path: path.to_string(),
+            digest: digest.to_string(),
+        })
+        .collect();
+
+    diff
+}
+
+/// Compares versions `from` and `to` of the OCFL object at `path`.
+pub fn diff_versions<P: AsRef<Path>>(path: P, from: &str, to: &str) -> Result<VersionDiff, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory(p)?;
+    let old = inventory.version(Some(from))?;
+    let new = inventory.version(Some(to))?;
+
+    Ok(diff_states(&old.state, &new.state))
+}
+
+/// Inverts a state block into logical path -> lowercase digest.
+fn by_path(state: &DigestMap) -> BTreeMap<&str, String> {
+    state
+        .iter()
+        .flat_map(|(digest, paths)| {
+            paths
+                .iter()
+                .map(move |path| (path.as_str(), digest.to_lowercase()))
+        })
+        .collect()
+}
@@ -0,0 +1,180 @@
+use blake2::{
+    Blake2b, Blake2b512,
+    digest::consts::{U20, U32, U48},
+};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512, Sha512_256, digest::DynDigest};
+use std::{
+    fmt,
+    fs::File,
+    io::{Error, ErrorKind, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+/// A digest algorithm from the OCFL specification or the `0001-digest-algorithms` extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DigestAlgorithm {
+    Sha512,
+    Sha256,
+    Sha1,
+    Md5,
+    Blake2b512,
+    Blake2b160,
+    Blake2b256,
+    Blake2b384,
+    Sha512_256,
+    /// The size of the content in bytes, as a decimal number.
+    Size,
+}
+
+impl DigestAlgorithm {
+    /// Every supported algorithm, core algorithms first.
+    pub const ALL: [DigestAlgorithm; 10] = [
+        DigestAlgorithm::Sha512,
+        DigestAlgorithm::Sha256,
+        DigestAlgorithm::Sha1,
+        DigestAlgorithm::Md5,
+        DigestAlgorithm::Blake2b512,
+        DigestAlgorithm::Blake2b160,
+        DigestAlgorithm::Blake2b256,
+        DigestAlgorithm::Blake2b384,
+        DigestAlgorithm::Sha512_256,
+        DigestAlgorithm::Size,
+    ];
+
+    /// Returns the name used for the algorithm in inventories and sidecar file names.
+    pub fn name(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha512 => "sha512",
+            DigestAlgorithm::Sha256 => "sha256",
+            DigestAlgorithm::Sha1 => "sha1",
+            DigestAlgorithm::Md5 => "md5",
+            DigestAlgorithm::Blake2b512 => "blake2b-512",
+            DigestAlgorithm::Blake2b160 => "blake2b-160",
+            DigestAlgorithm::Blake2b256 => "blake2b-256",
+            DigestAlgorithm::Blake2b384 => "blake2b-384",
+            DigestAlgorithm::Sha512_256 => "sha512/256",
+            DigestAlgorithm::Size => "size",
+        }
+    }
+
+    /// Returns true for the algorithms allowed as an inventory's `digestAlgorithm`; the others
+    /// may only appear in `fixity` blocks.
+    pub fn is_core(self) -> bool {
+        matches!(self, DigestAlgorithm::Sha512 | DigestAlgorithm::Sha256)
+    }
+
+    /// Returns an error unless the algorithm may be an inventory's `digestAlgorithm`.
+    pub fn require_core(self) -> Result<Self, Error> {
+        if self.is_core() {
+            Ok(self)
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{self} may only be used for fixity, not as an inventory digest algorithm"),
+            ))
+        }
+    }
+
+    fn hasher(self) -> Hasher {
+        let digest: Box<dyn DynDigest> = match self {
+            DigestAlgorithm::Sha512 => Box::new(Sha512::new()),
+            DigestAlgorithm::Sha256 => Box::new(Sha256::new()),
+            DigestAlgorithm::Sha1 => Box::new(Sha1::new()),
+            DigestAlgorithm::Md5 => Box::new(Md5::new()),
+            DigestAlgorithm::Blake2b512 => Box::new(Blake2b512::new()),
+            DigestAlgorithm::Blake2b160 => Box::new(Blake2b::<U20>::new()),
+            DigestAlgorithm::Blake2b256 => Box::new(Blake2b::<U32>::new()),
+            DigestAlgorithm::Blake2b384 => Box::new(Blake2b::<U48>::new()),
+            DigestAlgorithm::Sha512_256 => Box::new(Sha512_256::new()),
+            DigestAlgorithm::Size => return Hasher::Size(0),
+        };
+        Hasher::Digest(digest)
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Error> {
+        DigestAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Unsupported,
+                    format!("unsupported digest algorithm {name}"),
+                )
+            })
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+enum Hasher {
+    Digest(Box<dyn DynDigest>),
+    Size(u64),
+}
+
+impl Hasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Digest(digest) => digest.update(data),
+            Hasher::Size(size) => *size += data.len() as u64,
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Hasher::Digest(digest) => to_hex(&digest.finalize()),
+            Hasher::Size(size) => size.to_string(),
+        }
+    }
+}
+
+/// Returns the lowercase hex digest of everything read from `reader`.
+pub fn hex_digest<R: Read>(algorithm: DigestAlgorithm, reader: R) -> Result<String, Error> {
+    let (_, digest) = copy_with_digest(algorithm, reader, std::io::sink())?;
+    Ok(digest)
+}
+
+/// Returns the lowercase hex digest of the file at `path`.
+pub fn file_digest<P: AsRef<Path>>(algorithm: DigestAlgorithm, path: P) -> Result<String, Error> {
+    hex_digest(algorithm, File::open(path)?)
+}
+
+/// Copies `reader` to `writer`, returning the number of bytes copied and their hex digest.
+pub fn copy_with_digest<R: Read, W: Write>(
+    algorithm: DigestAlgorithm,
+    mut reader: R,
+    mut writer: W,
+) -> Result<(u64, String), Error> {
+    let mut hasher = algorithm.hasher();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut total = 0u64;
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..n]);
+        writer.write_all(&buffer[..n])?;
+        total += n as u64;
+    }
+    writer.flush()?;
+
+    Ok((total, hasher.finish()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
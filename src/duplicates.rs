@@ -0,0 +1,55 @@
+use crate::crawl::{ObjectRecord, object_records};
+use std::{collections::BTreeMap, io::Error, path::Path};
+
+/// Every object path sharing one identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    pub id: String,
+    pub objects: Vec<ObjectRecord>,
+}
+
+impl DuplicateGroup {
+    /// Returns true if every copy has the same head version.
+    pub fn heads_match(&self) -> bool {
+        self.objects.windows(2).all(|w| w[0].head == w[1].head)
+    }
+
+    /// Returns true if every copy has a readable sidecar and all sidecar digests are equal.
+    pub fn inventories_match(&self) -> bool {
+        self.objects.iter().all(|o| o.inventory_digest.is_some())
+            && self
+                .objects
+                .windows(2)
+                .all(|w| w[0].inventory_digest == w[1].inventory_digest)
+    }
+}
+
+/// Duplicate identifiers found across storage roots, along with the errors met while crawling.
+#[derive(Debug, Default)]
+pub struct Duplicates {
+    pub groups: Vec<DuplicateGroup>,
+    pub errors: Vec<Error>,
+}
+
+/// Crawls `paths` and groups objects by identifier, returning every identifier that is used by
+/// more than one object path (within one storage root or across several).
+pub fn find_duplicates<P: AsRef<Path>>(paths: &[P]) -> Duplicates {
+    let mut by_id: BTreeMap<String, Vec<ObjectRecord>> = BTreeMap::new();
+    let mut errors = Vec::new();
+
+    for path in paths {
+        let (records, crawl_errors) = object_records(path);
+        errors.extend(crawl_errors);
+        for record in records {
+            by_id.entry(record.id.clone()).or_default().push(record);
+        }
+    }
+
+    let groups = by_id
+        .into_iter()
+        .filter(|(_, objects)| objects.len() > 1)
+        .map(|(id, objects)| DuplicateGroup { id, objects })
+        .collect();
+
+    Duplicates { groups, errors }
+}
@@ -0,0 +1,122 @@
+use crate::digest::{DigestAlgorithm, copy_with_digest};
+use crate::inventory::{content_file_path, is_normal_relative_path, read_object_inventory};
+use crate::logical::{LogicalFile, ObjectVersion};
+use crate::object::is_object_root;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Error, ErrorKind},
+    path::Path,
+};
+
+/// Opens the content file backing `logical_path` in `version` (the head version if `None`)
+/// of the OCFL object at `path`.
+pub fn open_logical_file<P: AsRef<Path>>(
+    path: P,
+    version: Option<&str>,
+    logical_path: &str,
+) -> Result<File, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory(p)?;
+    let block = inventory.version(version)?;
+    let digest = block
+        .state
+        .iter()
+        .find(|(_, paths)| paths.iter().any(|path| path == logical_path))
+        .map(|(digest, _)| digest)
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("logical path {logical_path} not found"),
+            )
+        })?;
+    let content_path = inventory.content_path(digest).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("digest {digest} is not in the manifest"),
+        )
+    })?;
+
+    File::open(content_file_path(p, content_path)?)
+}
+
+/// Materializes the logical state of `version` (the head version if `None`) of the OCFL
+/// object at `path` into the directory `dest`, verifying each file's digest as it is copied.
+///
+/// `dest` must not exist or be an empty directory. Nothing is copied if any logical path would
+/// escape `dest`. Copying stops at the first file whose digest does not match the inventory;
+/// that file is removed.
+pub fn export_version<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    version: Option<&str>,
+    dest: Q,
+) -> Result<Vec<LogicalFile>, Error> {
+    let p = path.as_ref();
+    let dest = dest.as_ref();
+
+    let view = ObjectVersion::open(p, version)?;
+    let files: Vec<LogicalFile> = view.files().collect();
+    export_files(p, &files, view.inventory().algorithm()?, dest)?;
+
+    Ok(files)
+}
+
+/// Copies `files` of the object at `path` into `dest`, verifying each against its `algorithm`
+/// digest. See `export_version`.
+pub(crate) fn export_files(
+    path: &Path,
+    files: &[LogicalFile],
+    algorithm: DigestAlgorithm,
+    dest: &Path,
+) -> Result<(), Error> {
+    if dest.exists() && fs::read_dir(dest)?.next().is_some() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} is not empty", dest.display()),
+        ));
+    }
+    // Logical paths are relative and must not escape the export directory.
+    if let Some(file) = files
+        .iter()
+        .find(|file| !is_normal_relative_path(&file.logical_path))
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "refusing to export unsafe logical path {}",
+                file.logical_path
+            ),
+        ));
+    }
+    fs::create_dir_all(dest)?;
+
+    for file in files {
+        let target = dest.join(&file.logical_path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let source = File::open(content_file_path(path, &file.content_path)?)?;
+        let (_, digest) =
+            copy_with_digest(algorithm, source, BufWriter::new(File::create(&target)?))?;
+        if !digest.eq_ignore_ascii_case(&file.digest) {
+            let _ = fs::remove_file(&target);
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "digest mismatch for {}: expected {}, got {digest}",
+                    file.content_path, file.digest
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
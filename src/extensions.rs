@@ -0,0 +1,159 @@
+use crate::crawl::crawl;
+use crate::object::is_object_root;
+use crate::storage::is_storage_root;
+use crate::validate::ValidationIssue;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Extensions published in the OCFL extensions registry.
+pub const REGISTERED_EXTENSIONS: &[&str] = &[
+    "0001-digest-algorithms",
+    "0002-flat-direct-storage-layout",
+    "0003-hash-and-id-n-tuple-storage-layout",
+    "0004-hashed-n-tuple-storage-layout",
+    "0005-mutable-head",
+    "0006-flat-omit-prefix-storage-layout",
+    "0007-n-tuple-omit-prefix-storage-layout",
+    "0008-schema-registry",
+    "0009-digest-algorithms",
+    "0010-differential-ocfl-inventory",
+    "0011-direct-clean-path-layout",
+];
+
+/// Returns true if `name` is an extension from the OCFL extensions registry.
+pub fn is_registered_extension(name: &str) -> bool {
+    REGISTERED_EXTENSIONS.contains(&name)
+}
+
+/// Extensions found in the `extensions/` directories of a storage root and its objects.
+#[derive(Debug, Default)]
+pub struct ExtensionReport {
+    /// Extensions of the storage root itself.
+    pub root: Vec<String>,
+    /// Extensions of each object that has any, by object root.
+    pub objects: Vec<(PathBuf, Vec<String>)>,
+    /// Number of places (the root and each object) using each extension.
+    pub counts: BTreeMap<String, usize>,
+    /// Unregistered extensions (W013) and stray files in `extensions/` directories (E067).
+    pub issues: Vec<ValidationIssue>,
+    /// Directories the crawl could not read and objects whose extensions could not be listed.
+    pub errors: Vec<Error>,
+}
+
+/// Lists the extension directories in `extensions/` of the storage root or object root at
+/// `path`, sorted by name; empty if it has no `extensions/` directory.
+///
+/// Files directly inside `extensions/` are reported as E067 issues.
+pub fn list_extensions<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Vec<String>, Vec<ValidationIssue>), Error> {
+    let dir = path.as_ref().join("extensions");
+    let mut names = Vec::new();
+    let mut issues = Vec::new();
+    if !dir.is_dir() {
+        return Ok((names, issues));
+    }
+
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() {
+            names.push(name);
+        } else {
+            issues.push(ValidationIssue::error(
+                "E067",
+                format!(
+                    "{} is a file, not an extension directory",
+                    entry.path().display()
+                ),
+            ));
+        }
+    }
+    names.sort();
+    Ok((names, issues))
+}
+
+/// Reports the extensions used by the storage root at `path` and by every object below it.
+///
+/// Extensions missing from `REGISTERED_EXTENSIONS` are flagged as W013 warnings, once per
+/// place they appear. Directories and objects that cannot be read are recorded in `errors`
+/// and skipped.
+pub fn storage_root_extensions<P: AsRef<Path>>(path: P) -> Result<ExtensionReport, Error> {
+    let p = path.as_ref();
+
+    if !is_storage_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL storage root",
+        ));
+    }
+
+    let mut report = ExtensionReport::default();
+    let (root, issues) = list_extensions(p)?;
+    report.issues.extend(issues);
+    record(&mut report, p, &root);
+    report.root = root;
+
+    let result = crawl(p);
+    report.errors.extend(result.errors);
+    for object in result.objects {
+        let (names, issues) = match list_extensions(&object) {
+            Ok(listed) => listed,
+            Err(e) => {
+                report
+                    .errors
+                    .push(Error::new(e.kind(), format!("{}: {e}", object.display())));
+                continue;
+            }
+        };
+        report.issues.extend(issues);
+        if !names.is_empty() {
+            record(&mut report, &object, &names);
+            report.objects.push((object, names));
+        }
+    }
+
+    Ok(report)
+}
+
+/// Like `storage_root_extensions`, for a single object root.
+pub fn object_extensions<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Vec<String>, Vec<ValidationIssue>), Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let (names, mut issues) = list_extensions(p)?;
+    issues.extend(unregistered(p, &names));
+    Ok((names, issues))
+}
+
+fn record(report: &mut ExtensionReport, path: &Path, names: &[String]) {
+    for name in names {
+        *report.counts.entry(name.clone()).or_default() += 1;
+    }
+    report.issues.extend(unregistered(path, names));
+}
+
+fn unregistered(path: &Path, names: &[String]) -> Vec<ValidationIssue> {
+    names
+        .iter()
+        .filter(|name| !is_registered_extension(name))
+        .map(|name| {
+            ValidationIssue::warning(
+                "W013",
+                format!("{} uses unregistered extension {name}", path.display()),
+            )
+        })
+        .collect()
+}
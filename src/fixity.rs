@@ -0,0 +1,86 @@
+use crate::digest::{DigestAlgorithm, file_digest};
+use crate::inventory::{DigestMap, content_file_path, read_object_inventory, read_sidecar_digest};
+use crate::object::is_object_root;
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// A file whose digest does not match the one recorded for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixityIssue {
+    /// Path relative to the object root.
+    pub path: String,
+    /// Algorithm of the mismatched digest, e.g. "sha512" or "md5" for a `fixity` block.
+    pub algorithm: String,
+    pub expected: String,
+    /// Digest actually computed, or `None` if the file could not be read.
+    pub actual: Option<String>,
+}
+
+/// Verifies the root inventory against its sidecar and every manifest content file against its
+/// digest for the OCFL object at `path`.
+///
+/// Content files are also checked against each `fixity` block whose algorithm is a
+/// `DigestAlgorithm`; blocks in other algorithms are skipped.
+pub fn check_fixity<P: AsRef<Path>>(path: P) -> Result<Vec<FixityIssue>, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory(p)?;
+    let algorithm = inventory.algorithm()?;
+    let mut issues = Vec::new();
+
+    let expected = read_sidecar_digest(p, algorithm)?;
+    let actual = file_digest(algorithm, p.join("inventory.json")).ok();
+    if actual.as_deref() != Some(expected.as_str()) {
+        issues.push(FixityIssue {
+            path: String::from("inventory.json"),
+            algorithm: algorithm.to_string(),
+            expected,
+            actual,
+        });
+    }
+
+    check_digests(p, algorithm, &inventory.manifest, &mut issues);
+    for (name, block) in &inventory.fixity {
+        if let Ok(algorithm) = name.parse::<DigestAlgorithm>() {
+            check_digests(p, algorithm, block, &mut issues);
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Checks every content path in `digests` against its digest in `algorithm`.
+fn check_digests(
+    path: &Path,
+    algorithm: DigestAlgorithm,
+    digests: &DigestMap,
+    issues: &mut Vec<FixityIssue>,
+) {
+    for (digest, content_paths) in digests {
+        for content_path in content_paths {
+            let actual = content_file_path(path, content_path)
+                .and_then(|file| file_digest(algorithm, file))
+                .ok();
+            if !actual
+                .as_deref()
+                .is_some_and(|actual| actual.eq_ignore_ascii_case(digest))
+            {
+                issues.push(FixityIssue {
+                    path: content_path.clone(),
+                    algorithm: algorithm.to_string(),
+                    expected: digest.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+}
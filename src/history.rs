@@ -0,0 +1,60 @@
+use crate::diff::diff_states;
+use crate::inventory::{DigestMap, Inventory, User, read_object_inventory};
+use crate::object::is_object_root;
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// Metadata and change counts of a single version of an object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionSummary {
+    pub version: String,
+    pub created: String,
+    pub message: Option<String>,
+    pub user: Option<User>,
+    pub added: usize,
+    pub removed: usize,
+    pub modified: usize,
+    pub renamed: usize,
+}
+
+/// Summarizes every version of `inventory`, oldest first. Change counts are relative to the
+/// previous version (the first version counts all of its files as added).
+pub fn history(inventory: &Inventory) -> Vec<VersionSummary> {
+    let empty = DigestMap::new();
+    let mut previous = &empty;
+    let mut summaries = Vec::new();
+
+    for name in inventory.version_names() {
+        let version = &inventory.versions[name];
+        let diff = diff_states(previous, &version.state);
+        summaries.push(VersionSummary {
+            version: name.to_string(),
+            created: version.created.clone(),
+            message: version.message.clone(),
+            user: version.user.clone(),
+            added: diff.added.len(),
+            removed: diff.removed.len(),
+            modified: diff.modified.len(),
+            renamed: diff.renamed.len(),
+        });
+        previous = &version.state;
+    }
+
+    summaries
+}
+
+/// Summarizes every version of the OCFL object at `path`, oldest first.
+pub fn object_history<P: AsRef<Path>>(path: P) -> Result<Vec<VersionSummary>, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    Ok(history(&read_object_inventory(p)?))
+}
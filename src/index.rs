@@ -0,0 +1,458 @@
+use crate::crawl::crawl;
+use crate::inventory::{
+    Inventory, find_sidecar_digest, read_object_inventory, read_sidecar_digest,
+};
+use crate::object::get_object_spec_version;
+use crate::usage::usage_for;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{Connection, Row, params, params_from_iter};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS objects (
+    path TEXT PRIMARY KEY,
+    storage_root TEXT NOT NULL,
+    id TEXT NOT NULL,
+    spec_version TEXT NOT NULL,
+    head TEXT NOT NULL,
+    version_count INTEGER NOT NULL,
+    first_created TEXT,
+    last_created TEXT,
+    logical_bytes INTEGER NOT NULL,
+    physical_bytes INTEGER NOT NULL,
+    inventory_digest TEXT,
+    indexed_at TEXT NOT NULL,
+    dir_mtime INTEGER
+);
+CREATE INDEX IF NOT EXISTS objects_id ON objects (id);
+CREATE INDEX IF NOT EXISTS objects_storage_root ON objects (storage_root);
+CREATE TABLE IF NOT EXISTS versions (
+    object_path TEXT NOT NULL REFERENCES objects (path) ON DELETE CASCADE,
+    version TEXT NOT NULL,
+    created TEXT NOT NULL,
+    PRIMARY KEY (object_path, version)
+);
+PRAGMA foreign_keys = ON;
+";
+
+const COLUMNS: &str = "path, storage_root, id, spec_version, head, version_count, \
+    first_created, last_created, logical_bytes, physical_bytes, inventory_digest";
+
+/// A crawled object as recorded in the index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    pub path: PathBuf,
+    pub storage_root: PathBuf,
+    pub id: String,
+    pub spec_version: String,
+    pub head: String,
+    pub version_count: usize,
+    pub first_created: Option<String>,
+    pub last_created: Option<String>,
+    pub logical_bytes: u64,
+    pub physical_bytes: u64,
+    pub inventory_digest: Option<String>,
+}
+
+impl IndexEntry {
+    fn new(storage_root: &Path, path: &Path, inventory: &Inventory) -> Result<Self, Error> {
+        let usage = usage_for(path, inventory);
+        let names = inventory.version_names();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            storage_root: storage_root.to_path_buf(),
+            id: inventory.id.clone(),
+            spec_version: get_object_spec_version(path)?,
+            head: inventory.head.clone(),
+            version_count: names.len(),
+            first_created: names
+                .first()
+                .map(|name| inventory.versions[*name].created.clone()),
+            last_created: names
+                .last()
+                .map(|name| inventory.versions[*name].created.clone()),
+            logical_bytes: usage.logical_bytes,
+            physical_bytes: usage.bytes,
+            inventory_digest: inventory
+                .algorithm()
+                .and_then(|algorithm| read_sidecar_digest(path, algorithm))
+                .ok(),
+        })
+    }
+
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            path: PathBuf::from(row.get::<_, String>(0)?),
+            storage_root: PathBuf::from(row.get::<_, String>(1)?),
+            id: row.get(2)?,
+            spec_version: row.get(3)?,
+            head: row.get(4)?,
+            version_count: row.get::<_, i64>(5)? as usize,
+            first_created: row.get(6)?,
+            last_created: row.get(7)?,
+            logical_bytes: row.get::<_, i64>(8)? as u64,
+            physical_bytes: row.get::<_, i64>(9)? as u64,
+            inventory_digest: row.get(10)?,
+        })
+    }
+}
+
+/// Filters for `Index::query`; unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub id: Option<String>,
+    pub storage_root: Option<PathBuf>,
+    pub spec_version: Option<String>,
+    pub head: Option<String>,
+    /// Only objects with a version created at or after this RFC 3339 timestamp.
+    pub created_after: Option<String>,
+    /// Only objects with a version created before this RFC 3339 timestamp.
+    pub created_before: Option<String>,
+}
+
+/// Outcome of indexing a storage root.
+#[derive(Debug, Default)]
+pub struct IndexReport {
+    pub indexed: usize,
+    pub removed: usize,
+    pub errors: Vec<Error>,
+}
+
+/// Kind of change detected by an incremental update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    /// Lowercase name used in change feeds.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+/// An object that appeared, changed or disappeared since the previous index run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+    pub id: String,
+    /// Head version after the change, or the last indexed head for deletions.
+    pub head: String,
+}
+
+/// Outcome of an incremental update of a storage root.
+#[derive(Debug, Default)]
+pub struct ChangeFeed {
+    pub events: Vec<ChangeEvent>,
+    pub unchanged: usize,
+    pub errors: Vec<Error>,
+}
+
+/// Persistent SQLite index of crawl results.
+pub struct Index {
+    conn: Connection,
+}
+
+impl Index {
+    /// Opens (creating if needed) the index database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let conn = Connection::open(path).map_err(Error::other)?;
+        conn.execute_batch(SCHEMA).map_err(Error::other)?;
+        Ok(Self { conn })
+    }
+
+    /// Opens a throwaway in-memory index.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        let conn = Connection::open_in_memory().map_err(Error::other)?;
+        conn.execute_batch(SCHEMA).map_err(Error::other)?;
+        Ok(Self { conn })
+    }
+
+    /// Crawls the storage root at `path` and replaces its entries in the index.
+    ///
+    /// Paths are stored canonicalized so the index can be queried from any directory.
+    pub fn index_storage_root<P: AsRef<Path>>(&mut self, path: P) -> Result<IndexReport, Error> {
+        let root = path.as_ref().canonicalize()?;
+        let result = crawl(&root);
+        let mut report = IndexReport {
+            errors: result.errors,
+            ..IndexReport::default()
+        };
+
+        let tx = self.conn.transaction().map_err(Error::other)?;
+        let previous = indexed_paths(&tx, &root)?;
+        tx.execute(
+            "DELETE FROM objects WHERE storage_root = ?1",
+            params![root.display().to_string()],
+        )
+        .map_err(Error::other)?;
+
+        let mut current = HashSet::new();
+        for object in &result.objects {
+            match index_object(&tx, &root, object) {
+                Ok(entry) => {
+                    current.insert(entry.path.display().to_string());
+                    report.indexed += 1;
+                }
+                Err(e) => report
+                    .errors
+                    .push(Error::new(e.kind(), format!("{}: {e}", object.display()))),
+            }
+        }
+        tx.commit().map_err(Error::other)?;
+
+        report.removed = previous.difference(&current).count();
+        Ok(report)
+    }
+
+    /// Brings the entries of the storage root at `path` up to date, reopening only the
+    /// inventories of objects that are new or have changed since they were last indexed.
+    ///
+    /// An object is considered unchanged when both the modification time of its root directory
+    /// and the digest in its inventory sidecar match the indexed values.
+    pub fn update_storage_root<P: AsRef<Path>>(&mut self, path: P) -> Result<ChangeFeed, Error> {
+        let root = path.as_ref().canonicalize()?;
+        let result = crawl(&root);
+        let mut feed = ChangeFeed {
+            errors: result.errors,
+            ..ChangeFeed::default()
+        };
+
+        let tx = self.conn.transaction().map_err(Error::other)?;
+        let mut previous = indexed_states(&tx, &root)?;
+
+        for object in &result.objects {
+            let key = object.display().to_string();
+            let kind = match previous.remove(&key) {
+                Some(state) => {
+                    let digest = find_sidecar_digest(object).ok();
+                    if state.dir_mtime.is_some()
+                        && state.dir_mtime == modified_nanos(object)
+                        && state.inventory_digest.is_some()
+                        && state.inventory_digest == digest
+                    {
+                        feed.unchanged += 1;
+                        continue;
+                    }
+                    ChangeKind::Updated
+                }
+                None => ChangeKind::Created,
+            };
+
+            match index_object(&tx, &root, object) {
+                Ok(entry) => feed.events.push(ChangeEvent {
+                    kind,
+                    path: entry.path,
+                    id: entry.id,
+                    head: entry.head,
+                }),
+                Err(e) => feed
+                    .errors
+                    .push(Error::new(e.kind(), format!("{}: {e}", object.display()))),
+            }
+        }
+
+        for (path, state) in previous {
+            tx.execute("DELETE FROM objects WHERE path = ?1", params![path])
+                .map_err(Error::other)?;
+            feed.events.push(ChangeEvent {
+                kind: ChangeKind::Deleted,
+                path: PathBuf::from(path),
+                id: state.id,
+                head: state.head,
+            });
+        }
+        tx.commit().map_err(Error::other)?;
+
+        Ok(feed)
+    }
+
+    /// Returns the indexed objects matching `query`, ordered by path.
+    pub fn query(&self, query: &Query) -> Result<Vec<IndexEntry>, Error> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<String> = Vec::new();
+
+        let mut add = |condition: &str, value: Option<String>| {
+            if let Some(value) = value {
+                values.push(value);
+                conditions.push(condition.replace('?', &format!("?{}", values.len())));
+            }
+        };
+        add("id = ?", query.id.clone());
+        add(
+            "storage_root = ?",
+            query
+                .storage_root
+                .as_ref()
+                .map(|root| root.display().to_string()),
+        );
+        add("spec_version = ?", query.spec_version.clone());
+        add("head = ?", query.head.clone());
+        add(
+            "path IN (SELECT object_path FROM versions WHERE created >= ?)",
+            query
+                .created_after
+                .as_deref()
+                .map(query_bound)
+                .transpose()?,
+        );
+        add(
+            "path IN (SELECT object_path FROM versions WHERE created < ?)",
+            query
+                .created_before
+                .as_deref()
+                .map(query_bound)
+                .transpose()?,
+        );
+
+        let mut sql = format!("SELECT {COLUMNS} FROM objects");
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(" ORDER BY path");
+
+        let mut statement = self.conn.prepare(&sql).map_err(Error::other)?;
+        let rows = statement
+            .query_map(params_from_iter(values.iter()), IndexEntry::from_row)
+            .map_err(Error::other)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Error::other)
+    }
+}
+
+/// Reads the object at `path` and writes its entry and version timestamps to the index.
+fn index_object(conn: &Connection, storage_root: &Path, path: &Path) -> Result<IndexEntry, Error> {
+    let inventory = read_object_inventory(path)?;
+    let entry = IndexEntry::new(storage_root, path, &inventory)?;
+    let object_path = entry.path.display().to_string();
+    // Convert every timestamp before writing anything, so a malformed one leaves no row behind.
+    let created = inventory
+        .versions
+        .iter()
+        .map(|(name, version)| Ok((name, utc_timestamp(&version.created)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO objects ({COLUMNS}, indexed_at, dir_mtime) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
+        ),
+        params![
+            object_path,
+            entry.storage_root.display().to_string(),
+            entry.id,
+            entry.spec_version,
+            entry.head,
+            entry.version_count as i64,
+            entry.first_created,
+            entry.last_created,
+            entry.logical_bytes as i64,
+            entry.physical_bytes as i64,
+            entry.inventory_digest,
+            Utc::now().to_rfc3339(),
+            modified_nanos(path),
+        ],
+    )
+    .map_err(Error::other)?;
+
+    conn.execute(
+        "DELETE FROM versions WHERE object_path = ?1",
+        params![object_path],
+    )
+    .map_err(Error::other)?;
+    for (name, created) in created {
+        conn.execute(
+            "INSERT INTO versions (object_path, version, created) VALUES (?1, ?2, ?3)",
+            params![object_path, name, created],
+        )
+        .map_err(Error::other)?;
+    }
+
+    Ok(entry)
+}
+
+fn indexed_paths(conn: &Connection, storage_root: &Path) -> Result<HashSet<String>, Error> {
+    let mut statement = conn
+        .prepare("SELECT path FROM objects WHERE storage_root = ?1")
+        .map_err(Error::other)?;
+    let rows = statement
+        .query_map(params![storage_root.display().to_string()], |row| {
+            row.get(0)
+        })
+        .map_err(Error::other)?;
+    rows.collect::<rusqlite::Result<HashSet<String>>>()
+        .map_err(Error::other)
+}
+
+/// What an incremental update needs to know about a previously indexed object.
+struct IndexedState {
+    id: String,
+    head: String,
+    inventory_digest: Option<String>,
+    dir_mtime: Option<i64>,
+}
+
+fn indexed_states(
+    conn: &Connection,
+    storage_root: &Path,
+) -> Result<HashMap<String, IndexedState>, Error> {
+    let mut statement = conn
+        .prepare(
+            "SELECT path, id, head, inventory_digest, dir_mtime \
+             FROM objects WHERE storage_root = ?1",
+        )
+        .map_err(Error::other)?;
+    let rows = statement
+        .query_map(params![storage_root.display().to_string()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                IndexedState {
+                    id: row.get(1)?,
+                    head: row.get(2)?,
+                    inventory_digest: row.get(3)?,
+                    dir_mtime: row.get(4)?,
+                },
+            ))
+        })
+        .map_err(Error::other)?;
+    rows.collect::<rusqlite::Result<HashMap<_, _>>>()
+        .map_err(Error::other)
+}
+
+/// Converts an RFC 3339 timestamp to UTC with fixed nanosecond precision, so that stored
+/// timestamps compare correctly as text whatever offset the inventory used.
+fn utc_timestamp(timestamp: &str) -> Result<String, Error> {
+    let parsed = DateTime::parse_from_rfc3339(timestamp).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("invalid timestamp {timestamp}: {e}"),
+        )
+    })?;
+    Ok(parsed
+        .with_timezone(&Utc)
+        .to_rfc3339_opts(SecondsFormat::Nanos, true))
+}
+
+fn query_bound(timestamp: &str) -> Result<String, Error> {
+    utc_timestamp(timestamp).map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))
+}
+
+fn modified_nanos(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let nanos = modified.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    i64::try_from(nanos).ok()
+}
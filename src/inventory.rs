@@ -0,0 +1,348 @@
+use crate::backend::{Storage, join_path};
+use crate::digest::{DigestAlgorithm, hex_digest};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind},
+    path::{Component, Path, PathBuf},
+};
+
+/// Map of digests to the paths (content or logical) that carry them.
+pub type DigestMap = BTreeMap<String, Vec<String>>;
+
+/// Parsed OCFL inventory (`inventory.json`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    pub id: String,
+    pub type_: String,
+    pub digest_algorithm: String,
+    pub head: String,
+    pub content_directory: Option<String>,
+    pub manifest: DigestMap,
+    pub versions: BTreeMap<String, Version>,
+    pub fixity: BTreeMap<String, DigestMap>,
+}
+
+/// A single entry of the inventory `versions` block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Version {
+    pub created: String,
+    pub message: Option<String>,
+    pub user: Option<User>,
+    pub state: DigestMap,
+}
+
+/// The agent recorded for a version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub name: String,
+    pub address: Option<String>,
+}
+
+impl Inventory {
+    /// Parses an inventory from its JSON text.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        Self::from_value(&value)
+    }
+
+    /// Builds an inventory from an already parsed JSON value.
+    pub fn from_value(value: &Value) -> Result<Self, Error> {
+        let mut versions = BTreeMap::new();
+        let versions_value = value
+            .get("versions")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| invalid("inventory is missing a 'versions' object"))?;
+        for (name, version) in versions_value {
+            versions.insert(name.clone(), Version::from_value(name, version)?);
+        }
+
+        let mut fixity = BTreeMap::new();
+        if let Some(blocks) = value.get("fixity").and_then(|v| v.as_object()) {
+            for (algorithm, block) in blocks {
+                fixity.insert(algorithm.clone(), digest_map(block, "fixity")?);
+            }
+        }
+
+        Ok(Self {
+            id: required_str(value, "id")?,
+            type_: required_str(value, "type")?,
+            digest_algorithm: required_str(value, "digestAlgorithm")?,
+            head: required_str(value, "head")?,
+            content_directory: value
+                .get("contentDirectory")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            manifest: digest_map(
+                value
+                    .get("manifest")
+                    .ok_or_else(|| invalid("inventory is missing a 'manifest' object"))?,
+                "manifest",
+            )?,
+            versions,
+            fixity,
+        })
+    }
+
+    /// Serializes the inventory back to its JSON form, omitting empty optional blocks.
+    pub fn to_value(&self) -> Value {
+        let mut value = Map::new();
+        value.insert(String::from("id"), json!(self.id));
+        value.insert(String::from("type"), json!(self.type_));
+        value.insert(
+            String::from("digestAlgorithm"),
+            json!(self.digest_algorithm),
+        );
+        value.insert(String::from("head"), json!(self.head));
+        if let Some(content_directory) = &self.content_directory {
+            value.insert(String::from("contentDirectory"), json!(content_directory));
+        }
+        value.insert(String::from("manifest"), json!(self.manifest));
+        let versions: Map<String, Value> = self
+            .versions
+            .iter()
+            .map(|(name, version)| (name.clone(), version.to_value()))
+            .collect();
+        value.insert(String::from("versions"), Value::Object(versions));
+        if !self.fixity.is_empty() {
+            value.insert(String::from("fixity"), json!(self.fixity));
+        }
+        Value::Object(value)
+    }
+
+    /// Serializes the inventory as pretty-printed JSON text.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_value()).expect("inventory values serialize")
+    }
+
+    /// Version names ordered by version number rather than lexically.
+    pub fn version_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.versions.keys().map(String::as_str).collect();
+        names.sort_by_key(|name| version_number(name).unwrap_or(u32::MAX));
+        names
+    }
+
+    /// Returns the version block for `version`, or for the head version if `None`.
+    pub fn version(&self, version: Option<&str>) -> Result<&Version, Error> {
+        let name = version.unwrap_or(&self.head);
+        self.versions.get(name).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("version {name} not found in inventory"),
+            )
+        })
+    }
+
+    /// Returns the first manifest content path holding the content with `digest`.
+    pub fn content_path(&self, digest: &str) -> Option<&str> {
+        self.content_paths(digest).first().map(String::as_str)
+    }
+
+    /// Returns every manifest content path holding the content with `digest`; empty if the
+    /// digest is not in the manifest.
+    pub fn content_paths(&self, digest: &str) -> &[String] {
+        self.manifest
+            .get(digest)
+            .or_else(|| {
+                // Digests are case-insensitive hex; fall back to a slower comparison.
+                self.manifest
+                    .iter()
+                    .find(|(d, _)| d.eq_ignore_ascii_case(digest))
+                    .map(|(_, paths)| paths)
+            })
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Name of the content directory used inside version directories.
+    pub fn content_directory(&self) -> &str {
+        self.content_directory.as_deref().unwrap_or("content")
+    }
+
+    /// Parses `digest_algorithm`, failing for names that are not a known digest algorithm.
+    pub fn algorithm(&self) -> Result<DigestAlgorithm, Error> {
+        self.digest_algorithm.parse()
+    }
+}
+
+impl Version {
+    fn to_value(&self) -> Value {
+        let mut value = Map::new();
+        value.insert(String::from("created"), json!(self.created));
+        if let Some(message) = &self.message {
+            value.insert(String::from("message"), json!(message));
+        }
+        if let Some(user) = &self.user {
+            let mut agent = Map::new();
+            agent.insert(String::from("name"), json!(user.name));
+            if let Some(address) = &user.address {
+                agent.insert(String::from("address"), json!(address));
+            }
+            value.insert(String::from("user"), Value::Object(agent));
+        }
+        value.insert(String::from("state"), json!(self.state));
+        Value::Object(value)
+    }
+
+    fn from_value(name: &str, value: &Value) -> Result<Self, Error> {
+        let user = match value.get("user") {
+            Some(user) => Some(User {
+                name: required_str(user, "name")
+                    .map_err(|_| invalid(&format!("version {name} has a user without a name")))?,
+                address: user
+                    .get("address")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+            }),
+            None => None,
+        };
+
+        Ok(Self {
+            created: required_str(value, "created")
+                .map_err(|_| invalid(&format!("version {name} is missing 'created'")))?,
+            message: value
+                .get("message")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            user,
+            state: digest_map(
+                value
+                    .get("state")
+                    .ok_or_else(|| invalid(&format!("version {name} is missing 'state'")))?,
+                "state",
+            )?,
+        })
+    }
+}
+
+/// Reads and parses the inventory file at `path`.
+pub fn read_inventory<P: AsRef<Path>>(path: P) -> Result<Inventory, Error> {
+    let contents = std::fs::read_to_string(path.as_ref())?;
+    Inventory::from_json(&contents)
+}
+
+/// Reads and parses `inventory.json` from the OCFL object root at `path`.
+pub fn read_object_inventory<P: AsRef<Path>>(path: P) -> Result<Inventory, Error> {
+    read_inventory(path.as_ref().join("inventory.json"))
+}
+
+/// Like `read_object_inventory`, for the object root at `path` within `storage`.
+pub fn read_object_inventory_in(storage: &dyn Storage, path: &str) -> Result<Inventory, Error> {
+    Inventory::from_json(&storage.read_to_string(&join_path(path, "inventory.json"))?)
+}
+
+/// Writes `inventory` as `inventory.json` in `dir`, along with its
+/// `inventory.json.<algorithm>` sidecar. Fails unless the inventory uses sha512 or sha256.
+pub fn write_inventory<P: AsRef<Path>>(dir: P, inventory: &Inventory) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let algorithm = inventory.algorithm()?.require_core()?;
+    let json = inventory.to_json();
+    let digest = hex_digest(algorithm, json.as_bytes())?;
+    std::fs::write(dir.join("inventory.json"), json)?;
+    std::fs::write(
+        dir.join(format!("inventory.json.{algorithm}")),
+        format!("{digest} inventory.json\n"),
+    )
+}
+
+/// Returns the digest recorded in the `inventory.json.<algorithm>` sidecar in `dir`.
+pub fn read_sidecar_digest<P: AsRef<Path>>(
+    dir: P,
+    algorithm: DigestAlgorithm,
+) -> Result<String, Error> {
+    let sidecar = dir.as_ref().join(format!("inventory.json.{algorithm}"));
+    let contents = std::fs::read_to_string(sidecar)?;
+    contents
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| invalid("inventory sidecar is empty"))
+}
+
+/// Returns the digest recorded in whichever `inventory.json.<algorithm>` sidecar exists in `dir`,
+/// without needing to parse the inventory to learn its digest algorithm.
+pub fn find_sidecar_digest<P: AsRef<Path>>(dir: P) -> Result<String, Error> {
+    let dir = dir.as_ref();
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().to_string();
+        if let Some(Ok(algorithm)) = name.strip_prefix("inventory.json.").map(str::parse) {
+            return read_sidecar_digest(dir, algorithm);
+        }
+    }
+    Err(Error::new(
+        ErrorKind::NotFound,
+        "no inventory sidecar found",
+    ))
+}
+
+/// Returns the location of the manifest `content_path` inside the object root at `root`.
+///
+/// Fails unless the path is relative and made only of normal components, so a crafted
+/// manifest cannot reach files outside the object.
+pub fn content_file_path<P: AsRef<Path>>(root: P, content_path: &str) -> Result<PathBuf, Error> {
+    if !is_normal_relative_path(content_path) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("refusing to read unsafe content path {content_path}"),
+        ));
+    }
+    Ok(root.as_ref().join(content_path))
+}
+
+/// Returns true if `path` is non-empty, relative and free of `.`, `..` and root components.
+pub(crate) fn is_normal_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// Returns the number of a version directory name such as "v3" or "v003".
+pub fn version_number(name: &str) -> Option<u32> {
+    let digits = name.strip_prefix('v')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Returns true if a version directory name uses zero-padding ("v001").
+pub fn is_zero_padded(name: &str) -> bool {
+    name.strip_prefix('v')
+        .is_some_and(|digits| digits.len() > 1 && digits.starts_with('0'))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn required_str(value: &Value, field: &str) -> Result<String, Error> {
+    value
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .ok_or_else(|| invalid(&format!("inventory is missing a string '{field}' field")))
+}
+
+fn digest_map(value: &Value, block: &str) -> Result<DigestMap, Error> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| invalid(&format!("'{block}' must be a JSON object")))?;
+
+    let mut map = DigestMap::new();
+    for (digest, paths) in object {
+        let paths = paths
+            .as_array()
+            .ok_or_else(|| invalid(&format!("'{block}' entry {digest} must be an array")))?
+            .iter()
+            .map(|p| {
+                p.as_str()
+                    .map(String::from)
+                    .ok_or_else(|| invalid(&format!("'{block}' entry {digest} must hold strings")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        map.insert(digest.clone(), paths);
+    }
+    Ok(map)
+}
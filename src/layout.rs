@@ -0,0 +1,288 @@
+use crate::digest::hex_digest;
+use serde_json::{Value, json};
+use std::{
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// A storage layout extension mapping object identifiers to paths below a storage root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Layout {
+    /// `0002-flat-direct-storage-layout`: the identifier itself is the object root directory.
+    FlatDirect,
+    /// `0003-hash-and-id-n-tuple-storage-layout`: tuples of the identifier's hash followed by
+    /// the percent-encoded identifier.
+    HashAndIdNTuple {
+        digest_algorithm: String,
+        tuple_size: usize,
+        number_of_tuples: usize,
+    },
+    /// `0004-hashed-n-tuple-storage-layout`: tuples of the identifier's hash followed by the hash.
+    HashedNTuple {
+        digest_algorithm: String,
+        tuple_size: usize,
+        number_of_tuples: usize,
+        short_object_root: bool,
+    },
+    /// `0006-flat-omit-prefix-storage-layout`: the part of the identifier after the last
+    /// occurrence of `delimiter`.
+    FlatOmitPrefix { delimiter: String },
+}
+
+impl Layout {
+    /// Reads the layout declared by `ocfl_layout.json` in the storage root at `path`, along with
+    /// its `extensions/<name>/config.json` if present.
+    ///
+    /// Returns `None` if the storage root does not declare a layout.
+    pub fn from_storage_root<P: AsRef<Path>>(path: P) -> Result<Option<Self>, Error> {
+        let root = path.as_ref();
+        let layout_file = root.join("ocfl_layout.json");
+        if !layout_file.is_file() {
+            return Ok(None);
+        }
+
+        let layout = read_json(&layout_file)?;
+        let extension = layout
+            .get("extension")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid("ocfl_layout.json is missing a string 'extension' field"))?;
+
+        let config_file = root.join("extensions").join(extension).join("config.json");
+        let config = if config_file.is_file() {
+            Some(read_json(&config_file)?)
+        } else {
+            None
+        };
+
+        Self::from_config(extension, config.as_ref()).map(Some)
+    }
+
+    /// Builds the layout for the extension named `extension` from its parsed `config.json`,
+    /// using the extension's defaults for missing parameters.
+    pub fn from_config(extension: &str, config: Option<&Value>) -> Result<Self, Error> {
+        let string = |key: &str, default: &str| -> Result<String, Error> {
+            match config.and_then(|c| c.get(key)) {
+                None => Ok(default.to_string()),
+                Some(v) => v
+                    .as_str()
+                    .map(String::from)
+                    .ok_or_else(|| invalid(&format!("{extension} '{key}' must be a string"))),
+            }
+        };
+        let number = |key: &str, default: usize| -> Result<usize, Error> {
+            match config.and_then(|c| c.get(key)) {
+                None => Ok(default),
+                Some(v) => v
+                    .as_u64()
+                    .map(|n| n as usize)
+                    .ok_or_else(|| invalid(&format!("{extension} '{key}' must be a number"))),
+            }
+        };
+
+        let layout = match extension {
+            "0002-flat-direct-storage-layout" => Layout::FlatDirect,
+            "0003-hash-and-id-n-tuple-storage-layout" => Layout::HashAndIdNTuple {
+                digest_algorithm: string("digestAlgorithm", "sha256")?,
+                tuple_size: number("tupleSize", 3)?,
+                number_of_tuples: number("numberOfTuples", 3)?,
+            },
+            "0004-hashed-n-tuple-storage-layout" => Layout::HashedNTuple {
+                digest_algorithm: string("digestAlgorithm", "sha256")?,
+                tuple_size: number("tupleSize", 3)?,
+                number_of_tuples: number("numberOfTuples", 3)?,
+                short_object_root: match config.and_then(|c| c.get("shortObjectRoot")) {
+                    None => false,
+                    Some(v) => v.as_bool().ok_or_else(|| {
+                        invalid(&format!("{extension} 'shortObjectRoot' must be a boolean"))
+                    })?,
+                },
+            },
+            "0006-flat-omit-prefix-storage-layout" => Layout::FlatOmitPrefix {
+                delimiter: match config.and_then(|c| c.get("delimiter")) {
+                    Some(Value::String(d)) if !d.is_empty() => d.clone(),
+                    _ => return Err(invalid(&format!("{extension} requires a 'delimiter'"))),
+                },
+            },
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::Unsupported,
+                    format!("unsupported storage layout extension {extension}"),
+                ));
+            }
+        };
+
+        if let Layout::HashAndIdNTuple {
+            tuple_size,
+            number_of_tuples,
+            ..
+        }
+        | Layout::HashedNTuple {
+            tuple_size,
+            number_of_tuples,
+            ..
+        } = &layout
+            && (*tuple_size == 0) != (*number_of_tuples == 0)
+        {
+            return Err(invalid(&format!(
+                "{extension} 'tupleSize' and 'numberOfTuples' must both be zero or both be non-zero"
+            )));
+        }
+
+        Ok(layout)
+    }
+
+    /// Name of the storage layout extension.
+    pub fn extension_name(&self) -> &'static str {
+        match self {
+            Layout::FlatDirect => "0002-flat-direct-storage-layout",
+            Layout::HashAndIdNTuple { .. } => "0003-hash-and-id-n-tuple-storage-layout",
+            Layout::HashedNTuple { .. } => "0004-hashed-n-tuple-storage-layout",
+            Layout::FlatOmitPrefix { .. } => "0006-flat-omit-prefix-storage-layout",
+        }
+    }
+
+    /// One-sentence summary written to `ocfl_layout.json` as its `description`.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Layout::FlatDirect => {
+                "Object identifiers are used directly as object root directory names."
+            }
+            Layout::HashAndIdNTuple { .. } => {
+                "Object identifiers are hashed into n-tuple directories, followed by the \
+                 percent-encoded identifier."
+            }
+            Layout::HashedNTuple { .. } => {
+                "Object identifiers are hashed and the digest is divided into n-tuple \
+                 directories, followed by the digest."
+            }
+            Layout::FlatOmitPrefix { .. } => {
+                "Object identifiers are used as object root directory names after removing \
+                 everything up to the last delimiter."
+            }
+        }
+    }
+
+    /// Returns the extension's `config.json` contents, with every parameter spelled out.
+    pub fn to_config(&self) -> Value {
+        let name = self.extension_name();
+        match self {
+            Layout::FlatDirect => json!({ "extensionName": name }),
+            Layout::HashAndIdNTuple {
+                digest_algorithm,
+                tuple_size,
+                number_of_tuples,
+            } => json!({
+                "extensionName": name,
+                "digestAlgorithm": digest_algorithm,
+                "tupleSize": tuple_size,
+                "numberOfTuples": number_of_tuples,
+            }),
+            Layout::HashedNTuple {
+                digest_algorithm,
+                tuple_size,
+                number_of_tuples,
+                short_object_root,
+            } => json!({
+                "extensionName": name,
+                "digestAlgorithm": digest_algorithm,
+                "tupleSize": tuple_size,
+                "numberOfTuples": number_of_tuples,
+                "shortObjectRoot": short_object_root,
+            }),
+            Layout::FlatOmitPrefix { delimiter } => json!({
+                "extensionName": name,
+                "delimiter": delimiter,
+            }),
+        }
+    }
+
+    /// Returns the path of the object root for `id`, relative to the storage root and using `/`
+    /// as separator.
+    pub fn object_path(&self, id: &str) -> Result<String, Error> {
+        let path = match self {
+            Layout::FlatDirect => id.to_string(),
+            Layout::HashAndIdNTuple {
+                digest_algorithm,
+                tuple_size,
+                number_of_tuples,
+            } => {
+                let digest = hex_digest(digest_algorithm.parse()?, id.as_bytes())?;
+                let mut encoded = percent_encode(id);
+                // Long identifiers are truncated and disambiguated by their hash.
+                if encoded.len() > 100 {
+                    encoded.truncate(100);
+                    encoded = format!("{encoded}-{digest}");
+                }
+                let mut parts = tuples(&digest, *tuple_size, *number_of_tuples)?;
+                parts.push(encoded);
+                parts.join("/")
+            }
+            Layout::HashedNTuple {
+                digest_algorithm,
+                tuple_size,
+                number_of_tuples,
+                short_object_root,
+            } => {
+                let digest = hex_digest(digest_algorithm.parse()?, id.as_bytes())?;
+                let mut parts = tuples(&digest, *tuple_size, *number_of_tuples)?;
+                if *short_object_root {
+                    parts.push(digest[tuple_size * number_of_tuples..].to_string());
+                } else {
+                    parts.push(digest);
+                }
+                parts.join("/")
+            }
+            Layout::FlatOmitPrefix { delimiter } => match id.rfind(delimiter.as_str()) {
+                Some(index) => id[index + delimiter.len()..].to_string(),
+                None => id.to_string(),
+            },
+        };
+
+        // Flat layouts use the identifier as a directory name, which must stay a single segment.
+        let flat = matches!(self, Layout::FlatDirect | Layout::FlatOmitPrefix { .. });
+        if path.is_empty() || path == "." || path == ".." || (flat && path.contains('/')) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "identifier {id} cannot be mapped with {}",
+                    self.extension_name()
+                ),
+            ));
+        }
+
+        Ok(path)
+    }
+}
+
+fn tuples(digest: &str, tuple_size: usize, number_of_tuples: usize) -> Result<Vec<String>, Error> {
+    if tuple_size * number_of_tuples > digest.len() {
+        return Err(invalid(
+            "layout tuples are longer than the identifier digest",
+        ));
+    }
+    Ok((0..number_of_tuples)
+        .map(|n| digest[n * tuple_size..(n + 1) * tuple_size].to_string())
+        .collect())
+}
+
+/// Percent-encodes every byte other than ASCII letters, digits, `-` and `_` with lowercase hex.
+fn percent_encode(id: &str) -> String {
+    id.bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+                char::from(b).to_string()
+            } else {
+                format!("%{b:02x}")
+            }
+        })
+        .collect()
+}
+
+fn read_json(path: &Path) -> Result<Value, Error> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
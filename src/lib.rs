@@ -1,7 +1,47 @@
 //! Crawler library for OCFL objects and their content.
+pub mod archive;
+pub mod backend;
+pub mod commit;
+pub mod compare;
+pub mod crawl;
+pub mod create;
+pub mod dedup;
+pub mod diff;
+pub mod digest;
+pub mod duplicates;
+pub mod export;
+pub mod extensions;
+pub mod fixity;
+pub mod history;
+pub mod index;
+pub mod inventory;
+pub mod layout;
+pub mod logical;
+pub mod memory;
+pub mod mutable_head;
 pub mod object;
+pub mod s3;
+pub mod server;
+pub mod stats;
 pub mod storage;
+pub mod usage;
 pub mod utils;
+pub mod validate;
+#[cfg(target_os = "linux")]
+pub mod watch;
+pub mod webhook;
+pub use commit::commit_version;
+pub use compare::compare_roots;
+pub use crawl::crawl;
+pub use create::create_object;
+pub use dedup::analyze_dedup;
+pub use diff::diff_versions;
+pub use duplicates::find_duplicates;
+pub use export::{export_version, open_logical_file};
+pub use fixity::check_fixity;
+pub use history::object_history;
+pub use inventory::{Inventory, read_object_inventory};
+pub use logical::{ObjectVersion, logical_files};
 pub use object::get_object_id;
 pub use object::is_object_root;
 use std::{
@@ -10,6 +50,7 @@ use std::{
 };
 pub use storage::is_storage_root;
 pub use utils::is_directory;
+pub use validate::check_version_consistency;
 
 /// Guard that switches to a directory on creation and restores the previous CWD on drop.
 pub struct DirGuard {
@@ -0,0 +1,194 @@
+use crate::inventory::{Inventory, content_file_path, read_object_inventory};
+use crate::object::is_object_root;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// A file in the logical state of an object version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalFile {
+    pub logical_path: String,
+    pub digest: String,
+    pub content_path: String,
+    pub size: Option<u64>,
+}
+
+/// Returns the logical files of `version` (the head version if `None`) of the OCFL object at
+/// `path`, sorted by logical path.
+///
+/// Each logical path is resolved through the manifest to the content path that stores it.
+/// The size is `None` if the content file cannot be read.
+pub fn logical_files<P: AsRef<Path>>(
+    path: P,
+    version: Option<&str>,
+) -> Result<Vec<LogicalFile>, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory(p)?;
+    inventory_logical_files(p, &inventory, version)
+}
+
+/// Like `logical_files`, for an already read `inventory` whose manifest paths are relative to
+/// the object root at `path`.
+pub fn inventory_logical_files<P: AsRef<Path>>(
+    path: P,
+    inventory: &Inventory,
+    version: Option<&str>,
+) -> Result<Vec<LogicalFile>, Error> {
+    let view = ObjectVersion::from_inventory(path, inventory, version)?;
+    Ok(view.files().collect())
+}
+
+/// One version of an OCFL object, mapping between the logical paths of its state and the
+/// content paths that store them.
+///
+/// Content paths are relative to the object root and taken from the manifest as written, so
+/// they include the object's `contentDirectory`, e.g. "v1/data/a.txt" for an object that
+/// declares "data".
+///
+/// The view owns its inventory when opened from disk and borrows it when built with
+/// `from_inventory`.
+#[derive(Debug, Clone)]
+pub struct ObjectVersion<'a> {
+    root: PathBuf,
+    inventory: Cow<'a, Inventory>,
+    name: String,
+    /// Digest of each logical path in the version's state.
+    digests: BTreeMap<String, String>,
+}
+
+impl<'a> ObjectVersion<'a> {
+    /// Opens `version` (the head version if `None`) of the OCFL object at `path`.
+    pub fn open<P: AsRef<Path>>(path: P, version: Option<&str>) -> Result<Self, Error> {
+        let p = path.as_ref();
+
+        if !is_object_root(p) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Not an OCFL object root",
+            ));
+        }
+
+        let inventory = read_object_inventory(p)?;
+        Self::new(p, Cow::Owned(inventory), version)
+    }
+
+    /// Like `open`, borrowing an already read `inventory` whose manifest paths are relative to the
+    /// object root at `path`.
+    pub fn from_inventory<P: AsRef<Path>>(
+        path: P,
+        inventory: &'a Inventory,
+        version: Option<&str>,
+    ) -> Result<Self, Error> {
+        Self::new(path, Cow::Borrowed(inventory), version)
+    }
+
+    fn new<P: AsRef<Path>>(
+        path: P,
+        inventory: Cow<'a, Inventory>,
+        version: Option<&str>,
+    ) -> Result<Self, Error> {
+        let block = inventory.version(version)?;
+
+        let mut digests = BTreeMap::new();
+        for (digest, logical_paths) in &block.state {
+            if inventory.content_paths(digest).is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("digest {digest} is not in the manifest"),
+                ));
+            }
+            for logical_path in logical_paths {
+                digests.insert(logical_path.clone(), digest.clone());
+            }
+        }
+
+        Ok(Self {
+            root: path.as_ref().to_path_buf(),
+            name: version.unwrap_or(&inventory.head).to_string(),
+            inventory,
+            digests,
+        })
+    }
+
+    /// Name of the version, e.g. "v2".
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The inventory the version was read from.
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    /// Number of logical files in the version.
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Returns true if the version has no logical files.
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// Returns the digest of the file at `logical_path`, or `None` if the version has no such
+    /// file.
+    pub fn digest(&self, logical_path: &str) -> Option<&str> {
+        self.digests.get(logical_path).map(String::as_str)
+    }
+
+    /// Returns every content path storing the file at `logical_path`, or `None` if the version
+    /// has no such file.
+    pub fn resolve(&self, logical_path: &str) -> Option<&[String]> {
+        self.digest(logical_path)
+            .map(|digest| self.inventory.content_paths(digest))
+    }
+
+    /// Returns the logical paths in this version whose content is stored at `content_path`,
+    /// sorted; empty if the manifest has no such path or the version does not use its content.
+    pub fn logical_paths_for(&self, content_path: &str) -> Vec<&str> {
+        let Some(digest) = self
+            .inventory
+            .manifest
+            .iter()
+            .find(|(_, paths)| paths.iter().any(|p| p == content_path))
+            .map(|(digest, _)| digest)
+        else {
+            return Vec::new();
+        };
+        self.digests
+            .iter()
+            .filter(|(_, d)| d.eq_ignore_ascii_case(digest))
+            .map(|(logical_path, _)| logical_path.as_str())
+            .collect()
+    }
+
+    /// Iterates over the logical files of the version, sorted by logical path.
+    ///
+    /// Each file carries the first content path storing it; the size is `None` if that content
+    /// file cannot be read.
+    pub fn files(&self) -> impl Iterator<Item = LogicalFile> + '_ {
+        self.digests.iter().map(|(logical_path, digest)| {
+            let content_path = self.inventory.content_path(digest).unwrap_or_default();
+            LogicalFile {
+                logical_path: logical_path.clone(),
+                digest: digest.clone(),
+                content_path: content_path.to_string(),
+                size: content_file_path(&self.root, content_path)
+                    .and_then(std::fs::metadata)
+                    .ok()
+                    .map(|m| m.len()),
+            }
+        })
+    }
+}
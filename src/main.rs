@@ -1,10 +1,51 @@
-use anyhow::{anyhow, Result};
-use clap::{builder::PossibleValue, ArgAction, Parser, Subcommand, ValueEnum};
-use ocfl_crawler_rust::{get_object_id, is_object_root, is_storage_root, DirGuard};
+use anyhow::{Result, anyhow};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum, builder::PossibleValue};
+use ocfl_crawler_rust::{
+    DirGuard, analyze_dedup,
+    backend::{Storage, is_archive, is_remote, open_storage},
+    check_fixity, commit_version,
+    compare::{compare_content, compare_fixity},
+    compare_roots,
+    crawl::{crawl, crawl_in},
+    create::VersionInfo,
+    create_object,
+    diff::{FileChange, VersionDiff},
+    diff_versions,
+    digest::DigestAlgorithm,
+    export_version,
+    extensions::{is_registered_extension, object_extensions, storage_root_extensions},
+    find_duplicates,
+    fixity::FixityIssue,
+    get_object_id,
+    index::{ChangeEvent, Index, IndexEntry, Query},
+    inventory::{User, read_object_inventory},
+    is_object_root, is_storage_root,
+    layout::Layout,
+    logical_files,
+    mutable_head::{
+        MutableHead, export_mutable_head, mutable_head_files, read_mutable_head,
+        read_mutable_head_in,
+    },
+    object::{get_object_id_in, is_object_root_in},
+    object_history, open_logical_file,
+    server::Server,
+    stats::{Summary, summarize},
+    storage::{init_storage_root, is_storage_root_in},
+    usage::{ObjectUsage, object_usage, storage_root_usage},
+    validate::{Severity, check_version_consistency_in},
+    webhook::{Webhook, parse_header},
+};
 use regex::Regex;
-use serde_json::to_string;
-use std::path::Path;
-use walkdir::{DirEntry, WalkDir};
+use serde_json::{json, to_string};
+use std::{
+    cmp::Reverse,
+    io::{self, Write},
+    path::Path,
+};
+use walkdir::DirEntry;
+
+#[cfg(target_os = "linux")]
+use ocfl_crawler_rust::watch::Watcher;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, propagate_version = true)]
@@ -21,11 +62,53 @@ enum Command {
     List(ListCmd),
     /// Show info for a single OCFL object root
     Info(InfoCmd),
+    /// Check an OCFL object's version directories and inventories for consistency
+    Validate(ValidateCmd),
+    /// List the logical files of an OCFL object version
+    #[command(disable_version_flag = true)]
+    Ls(LsCmd),
+    /// Write the content of a logical file of an OCFL object version to stdout
+    #[command(disable_version_flag = true)]
+    Cat(CatCmd),
+    /// Copy the logical state of an OCFL object version into a directory
+    #[command(disable_version_flag = true)]
+    Export(ExportCmd),
+    /// Show the logical changes between two versions of an OCFL object
+    Diff(DiffCmd),
+    /// Show the version history of an OCFL object, newest first
+    History(HistoryCmd),
+    /// Report storage usage per object, per version and per storage root
+    Du(DuCmd),
+    /// Report object identifiers that appear at more than one object path
+    Duplicates(DuplicatesCmd),
+    /// Report content duplicated across the objects of one or more storage roots
+    AnalyzeDedup(AnalyzeDedupCmd),
+    /// Verify inventory sidecars and content digests of an OCFL object
+    Fixity(FixityCmd),
+    /// Compare the objects of two storage roots (e.g. a replica) by identifier
+    Compare(CompareCmd),
+    /// Record crawl results for one or more storage roots in a SQLite index
+    Index(IndexCmd),
+    /// Look up objects in a SQLite index built by `index`
+    Query(QueryCmd),
+    /// Serve a read-only HTTP API over a storage root
+    Serve(ServeCmd),
+    /// Print an event whenever an object is created or updated in a storage root
+    #[cfg(target_os = "linux")]
+    Watch(WatchCmd),
+    /// Create a new OCFL object in a storage root from the files of a directory
+    Create(CreateCmd),
+    /// Add a version to an OCFL object whose state is the files of a directory
+    Commit(CommitCmd),
+    /// Initialize an empty OCFL storage root with a storage layout extension
+    InitRoot(InitRootCmd),
+    /// Report the extensions used by storage roots and their objects
+    Extensions(ExtensionsCmd),
 }
 
 #[derive(Debug, clap::Args)]
 struct ListCmd {
-    /// OCFL Storage Root path(s)
+    /// OCFL Storage Root path(s), .tar/.tar.gz/.zip archive(s) or s3://bucket/prefix URL(s)
     #[arg(value_name = "PATH", default_value = ".")]
     paths: Vec<String>,
 
@@ -66,13 +149,380 @@ struct ListCmd {
     /// Include identifier in output
     #[arg(long)]
     identifier: bool,
+
+    /// Print aggregate statistics instead of one line per object
+    #[arg(long)]
+    summary: bool,
+
+    #[command(flatten)]
+    post: PostArgs,
 }
 
 #[derive(Debug, clap::Args)]
 struct InfoCmd {
+    /// Path, .tar/.tar.gz/.zip archive or s3:// URL of an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "PATH")]
+    path: String,
+}
+
+#[derive(Debug, clap::Args)]
+struct ValidateCmd {
+    /// Path, .tar/.tar.gz/.zip archive or s3:// URL of an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "PATH")]
+    path: String,
+
+    #[command(flatten)]
+    post: PostArgs,
+}
+
+#[derive(Debug, clap::Args)]
+struct LsCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Version to list (defaults to the head version)
+    #[arg(long, value_name = "VERSION")]
+    version: Option<String>,
+
+    /// List the uncommitted mutable head (0005-mutable-head extension) instead
+    #[arg(long, conflicts_with = "version")]
+    mutable_head: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct CatCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Logical path of the file within the version
+    #[arg(value_name = "LOGICAL_PATH")]
+    logical_path: String,
+
+    /// Version to read from (defaults to the head version)
+    #[arg(long, value_name = "VERSION")]
+    version: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct ExportCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Destination directory (must not exist or be empty)
+    #[arg(value_name = "DEST")]
+    dest: String,
+
+    /// Version to export (defaults to the head version)
+    #[arg(long, value_name = "VERSION")]
+    version: Option<String>,
+
+    /// Export the uncommitted mutable head (0005-mutable-head extension) instead
+    #[arg(long, conflicts_with = "version")]
+    mutable_head: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct DiffCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Version to compare from
+    #[arg(value_name = "FROM")]
+    from: String,
+
+    /// Version to compare to
+    #[arg(value_name = "TO")]
+    to: String,
+
+    /// Emit a single JSON document instead of one line per change
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct HistoryCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Emit one JSON line per version
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct DuCmd {
+    /// OCFL Storage Root or object root path(s)
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Only report the N largest objects of each storage root
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+
+    /// Include per-version usage for each object
+    #[arg(long)]
+    versions: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct DuplicatesCmd {
+    /// OCFL Storage Root path(s)
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct AnalyzeDedupCmd {
+    /// OCFL Storage Root path(s)
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Only report the N contents with the most duplicated bytes
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
+}
+
+#[derive(Debug, clap::Args)]
+struct FixityCmd {
+    /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    #[command(flatten)]
+    post: PostArgs,
+}
+
+#[derive(Debug, clap::Args)]
+struct CompareCmd {
+    /// First OCFL Storage Root path
+    #[arg(value_name = "ROOT_A")]
+    root_a: String,
+
+    /// Second OCFL Storage Root path
+    #[arg(value_name = "ROOT_B")]
+    root_b: String,
+
+    /// Also verify the content digests of every object present in both roots, and that both
+    /// copies record the same digests
+    #[arg(long)]
+    fixity: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct IndexCmd {
+    /// OCFL Storage Root path(s)
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+
+    /// Index database file
+    #[arg(long, value_name = "DB", default_value = "ocfl-index.sqlite")]
+    db: String,
+
+    /// Only reopen new or changed objects and print created/updated/deleted events
+    #[arg(long)]
+    incremental: bool,
+}
+
+#[derive(Debug, clap::Args)]
+struct QueryCmd {
+    /// Index database file
+    #[arg(long, value_name = "DB", default_value = "ocfl-index.sqlite")]
+    db: String,
+
+    /// Only objects with this identifier
+    #[arg(long, value_name = "ID")]
+    id: Option<String>,
+
+    /// Only objects under this storage root
+    #[arg(long, value_name = "PATH")]
+    storage_root: Option<String>,
+
+    /// Only objects declaring this OCFL specification version (e.g. 1.1)
+    #[arg(long, value_name = "VERSION")]
+    spec_version: Option<String>,
+
+    /// Only objects whose head is this version (e.g. v3)
+    #[arg(long, value_name = "VERSION")]
+    head: Option<String>,
+
+    /// Only objects with a version created at or after this RFC 3339 timestamp
+    #[arg(long, value_name = "TIMESTAMP")]
+    created_after: Option<String>,
+
+    /// Only objects with a version created before this RFC 3339 timestamp
+    #[arg(long, value_name = "TIMESTAMP")]
+    created_before: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct ServeCmd {
+    /// OCFL Storage Root path
+    #[arg(value_name = "ROOT")]
+    path: String,
+
+    /// Address to listen on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    listen: String,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, clap::Args)]
+struct WatchCmd {
+    /// OCFL Storage Root path
+    #[arg(value_name = "ROOT")]
+    path: String,
+
+    #[command(flatten)]
+    post: PostArgs,
+}
+
+#[derive(Debug, clap::Args)]
+struct CreateCmd {
+    /// OCFL Storage Root path; the object is placed according to its layout
+    #[arg(value_name = "ROOT")]
+    root: String,
+
+    /// Directory whose files become the object's first version
+    #[arg(value_name = "SOURCE_DIR")]
+    source: String,
+
+    /// Identifier of the new object
+    #[arg(long, value_name = "ID")]
+    id: String,
+
+    /// Digest algorithm for the manifest, state and sidecars
+    #[arg(long, value_name = "ALGORITHM", default_value = "sha512")]
+    digest_algorithm: DigestAlgorithm,
+
+    #[command(flatten)]
+    version: VersionArgs,
+}
+
+#[derive(Debug, clap::Args)]
+struct CommitCmd {
     /// Path to an OCFL object root (directory containing inventory.json)
+    #[arg(value_name = "OBJECT")]
+    path: String,
+
+    /// Directory holding the complete logical state of the new version
+    #[arg(value_name = "SOURCE_DIR")]
+    source: String,
+
+    #[command(flatten)]
+    version: VersionArgs,
+}
+
+#[derive(Debug, clap::Args)]
+struct InitRootCmd {
+    /// Directory to initialize (must not exist or be empty)
     #[arg(value_name = "PATH")]
     path: String,
+
+    /// OCFL specification version
+    #[arg(long, value_name = "VERSION", default_value = "1.1")]
+    spec: String,
+
+    /// Storage layout extension
+    #[arg(
+        long,
+        value_name = "EXTENSION",
+        default_value = "0004-hashed-n-tuple-storage-layout"
+    )]
+    layout: String,
+
+    /// Layout digest algorithm (0003, 0004)
+    #[arg(long, value_name = "ALGORITHM")]
+    digest_algorithm: Option<String>,
+
+    /// Number of digest characters per directory (0003, 0004)
+    #[arg(long, value_name = "N")]
+    tuple_size: Option<usize>,
+
+    /// Number of directory levels (0003, 0004)
+    #[arg(long, value_name = "N")]
+    number_of_tuples: Option<usize>,
+
+    /// Leave out the digest characters already used by the tuples from the object root name (0004)
+    #[arg(long)]
+    short_object_root: bool,
+
+    /// Delimiter after which the identifier names the object root (0006)
+    #[arg(long, value_name = "DELIMITER")]
+    delimiter: Option<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct ExtensionsCmd {
+    /// OCFL Storage Root or object root path(s)
+    #[arg(value_name = "PATH", default_value = ".")]
+    paths: Vec<String>,
+}
+
+#[derive(Debug, clap::Args)]
+struct VersionArgs {
+    /// Message recorded for the new version
+    #[arg(long, value_name = "MESSAGE")]
+    message: Option<String>,
+
+    /// Name of the user recorded for the new version
+    #[arg(long, value_name = "NAME")]
+    user_name: Option<String>,
+
+    /// Address (e.g. a mailto: URI) of the user recorded for the new version
+    #[arg(long, value_name = "ADDRESS", requires = "user_name")]
+    user_address: Option<String>,
+}
+
+impl VersionArgs {
+    fn info(&self) -> VersionInfo {
+        VersionInfo {
+            message: self.message.clone(),
+            user: self.user_name.as_ref().map(|name| User {
+                name: name.clone(),
+                address: self.user_address.clone(),
+            }),
+            created: None,
+        }
+    }
+}
+
+#[derive(Debug, clap::Args)]
+struct PostArgs {
+    /// Also POST output records as JSON arrays to this URL
+    #[arg(long, value_name = "URL")]
+    post_to: Option<String>,
+
+    /// Extra request header for --post-to, as "Name: value"
+    #[arg(long, value_name = "HEADER", requires = "post_to")]
+    post_header: Vec<String>,
+
+    /// Number of records sent per request
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    post_batch_size: usize,
+
+    /// Number of times a failed request is retried
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    post_retries: u32,
+}
+
+impl PostArgs {
+    fn webhook(&self) -> Result<Option<Webhook>> {
+        let Some(url) = &self.post_to else {
+            return Ok(None);
+        };
+        let mut webhook = Webhook::new(url);
+        for header in &self.post_header {
+            webhook.headers.push(parse_header(header)?);
+        }
+        webhook.batch_size = self.post_batch_size;
+        webhook.retries = self.post_retries;
+        Ok(Some(webhook))
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -103,6 +553,26 @@ fn main() {
     let result = match cli.command {
         Command::List(args) => run_list(args),
         Command::Info(args) => run_info(args),
+        Command::Validate(args) => run_validate(args),
+        Command::Ls(args) => run_ls(args),
+        Command::Cat(args) => run_cat(args),
+        Command::Export(args) => run_export(args),
+        Command::Diff(args) => run_diff(args),
+        Command::History(args) => run_history(args),
+        Command::Du(args) => run_du(args),
+        Command::Duplicates(args) => run_duplicates(args),
+        Command::AnalyzeDedup(args) => run_analyze_dedup(args),
+        Command::Fixity(args) => run_fixity(args),
+        Command::Compare(args) => run_compare(args),
+        Command::Index(args) => run_index(args),
+        Command::Query(args) => run_query(args),
+        Command::Serve(args) => run_serve(args),
+        #[cfg(target_os = "linux")]
+        Command::Watch(args) => run_watch(args),
+        Command::Create(args) => run_create(args),
+        Command::Commit(args) => run_commit(args),
+        Command::InitRoot(args) => run_init_root(args),
+        Command::Extensions(args) => run_extensions(args),
     };
 
     if let Err(e) = result {
@@ -116,40 +586,73 @@ fn run_list(args: ListCmd) -> Result<()> {
     let _type_filter = |entry: &DirEntry| {
         args.entry_types.is_empty()
             || args.entry_types.iter().any(|entry_type| match entry_type {
-            EntryType::Link => entry.file_type().is_symlink(),
-            EntryType::Dir => entry.file_type().is_dir(),
-            EntryType::File => entry.file_type().is_file(),
-        })
+                EntryType::Link => entry.file_type().is_symlink(),
+                EntryType::Dir => entry.file_type().is_dir(),
+                EntryType::File => entry.file_type().is_file(),
+            })
     };
 
     let _name_filter = |entry: &DirEntry| {
         args.names.is_empty()
             || args
-            .names
-            .iter()
-            .any(|re| re.is_match(&entry.file_name().to_string_lossy()))
+                .names
+                .iter()
+                .any(|re| re.is_match(&entry.file_name().to_string_lossy()))
     };
 
-    let object_filter = |entry: &DirEntry| is_object_root(entry.path());
+    let mut summary = Summary::default();
+    let mut webhook = args.post.webhook()?;
 
     for path in &args.paths {
-        if is_storage_root(path) {
+        if is_remote(path) || is_archive(path) {
+            if args.summary {
+                return Err(anyhow!(
+                    "--summary is not supported for remote or archived storage roots"
+                ));
+            }
+            let storage = open_storage(path)?;
+            if !is_storage_root_in(&*storage, "") {
+                eprintln!("{path} is not a storage root");
+                continue;
+            }
+
+            let result = crawl_in(&*storage, "");
+            for e in &result.errors {
+                eprintln!("{e}");
+            }
+            for object in &result.objects {
+                let entry = object_in_to_json(
+                    &*storage,
+                    path,
+                    &object.display().to_string(),
+                    args.key,
+                    args.identifier,
+                    args.namespace.as_deref(),
+                );
+                println!("{entry}");
+                if let Some(webhook) = &mut webhook {
+                    webhook.push(serde_json::from_str(&entry)?)?;
+                }
+            }
+        } else if is_storage_root(path) {
+            if args.summary {
+                summary.merge(summarize(path));
+                continue;
+            }
+
             let _guard = DirGuard::change_to(path)?;
 
-            let entries = WalkDir::new(".")
-                .min_depth(1)
-                .into_iter()
-                .filter_map(|e| match e {
-                    Err(e) => {
-                        eprintln!("{e}");
-                        None
-                    }
-                    Ok(entry) => Some(entry),
-                })
-                .filter(object_filter)
-                .map(|entry| {
+            let result = crawl(".");
+            for e in &result.errors {
+                eprintln!("{e}");
+            }
+
+            let entries = result
+                .objects
+                .iter()
+                .map(|object| {
                     object_to_json(
-                        entry.path().display().to_string(),
+                        object.display().to_string(),
                         args.absolute,
                         args.key,
                         args.identifier,
@@ -159,6 +662,9 @@ fn run_list(args: ListCmd) -> Result<()> {
                 .collect::<Vec<_>>();
             for entry in &entries {
                 println!("{entry}");
+                if let Some(webhook) = &mut webhook {
+                    webhook.push(serde_json::from_str(entry)?)?;
+                }
             }
         } else {
             let abs_path = Path::new(path).canonicalize()?;
@@ -167,10 +673,44 @@ fn run_list(args: ListCmd) -> Result<()> {
         }
     }
 
+    if args.summary {
+        emit(
+            json!({
+                "objects": summary.objects,
+                "spec_versions": summary.spec_versions,
+                "digest_algorithms": summary.digest_algorithms,
+                "version_counts": summary.version_counts,
+                "logical_bytes": summary.logical_bytes,
+                "physical_bytes": summary.physical_bytes,
+                "oldest_version": summary.oldest_version,
+                "newest_version": summary.newest_version,
+                "errors": summary.errors,
+            }),
+            &mut webhook,
+        )?;
+    }
+
+    if let Some(webhook) = &mut webhook {
+        webhook.flush()?;
+    }
     Ok(())
 }
 
 fn run_info(args: InfoCmd) -> Result<()> {
+    if is_remote(&args.path) || is_archive(&args.path) {
+        let storage = open_storage(&args.path)?;
+        if !is_object_root_in(&*storage, "") {
+            return Err(anyhow!(format!("{} is not an OCFL object root", args.path)));
+        }
+        let id = get_object_id_in(&*storage, "")?;
+        let mutable_head = mutable_head_to_json(read_mutable_head_in(&*storage, ""));
+        println!(
+            "{}",
+            object_fields_to_json(&args.path, Some(id), None, None, mutable_head)
+        );
+        return Ok(());
+    }
+
     let p = Path::new(&args.path);
     if !is_object_root(p) {
         let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
@@ -186,54 +726,969 @@ fn run_info(args: InfoCmd) -> Result<()> {
     Ok(())
 }
 
-pub fn object_to_json<P: AsRef<Path>>(
-    path: P,
-    absolute: bool,
-    key: bool,
-    identifier: bool,
-    namespace: Option<&str>,
-) -> String {
-    let path_ref = path.as_ref();
+fn run_validate(args: ValidateCmd) -> Result<()> {
+    let storage = open_storage(&args.path)?;
+    if !is_object_root_in(&*storage, "") {
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            display_location(&args.path)
+        )));
+    }
 
-    // Decide whether to emit an absolute or as-given path.
-    let path_str = if absolute {
-        path_ref
-            .canonicalize()
-            .unwrap_or_else(|_| path_ref.to_path_buf())
-            .display()
-            .to_string()
+    let mut webhook = args.post.webhook()?;
+    let issues = check_version_consistency_in(&*storage, "")?;
+    for issue in &issues {
+        let severity = match issue.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        emit(
+            json!({"code": issue.code, "severity": severity, "message": issue.message}),
+            &mut webhook,
+        )?;
+    }
+    if let Some(webhook) = &mut webhook {
+        webhook.flush()?;
+    }
+
+    if issues.iter().any(|issue| issue.is_error()) {
+        return Err(anyhow!(format!(
+            "{} is not a valid OCFL object",
+            display_location(&args.path)
+        )));
+    }
+    Ok(())
+}
+
+fn run_ls(args: LsCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
+
+    let files = if args.mutable_head {
+        mutable_head_files(p)?
     } else {
-        path_ref.display().to_string()
+        logical_files(p, args.version.as_deref())?
     };
+    for file in files {
+        println!(
+            "{}",
+            json!({
+                "logical_path": file.logical_path,
+                "digest": file.digest,
+                "content_path": file.content_path,
+                "size": file.size,
+            })
+        );
+    }
+    Ok(())
+}
 
-    // Build JSON manually to control key order: path, id, key, namespace.
-    let mut parts: Vec<String> = Vec::new();
+fn run_cat(args: CatCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
 
-    // Always include the path first.
-    let path_json = to_string(&path_str).unwrap();
-    parts.push(format!("\"path\":{path_json}"));
+    let mut file = open_logical_file(p, args.version.as_deref(), &args.logical_path)?;
+    io::copy(&mut file, &mut io::stdout().lock())?;
+    Ok(())
+}
 
-    if identifier {
-        let id_str = get_object_id(path_ref).unwrap_or_else(|_| String::from(""));
-        let id_json = to_string(&id_str).unwrap();
-        parts.push(format!("\"id\":{id_json}"));
+fn run_export(args: ExportCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
     }
 
-    if key {
-        let key_str = path_ref
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .to_string();
-        let key_json = to_string(&key_str).unwrap();
-        parts.push(format!("\"key\":{key_json}"));
+    let files = if args.mutable_head {
+        export_mutable_head(p, &args.dest)?
+    } else {
+        export_version(p, args.version.as_deref(), &args.dest)?
+    };
+    for file in files {
+        let target = Path::new(&args.dest).join(&file.logical_path);
+        println!(
+            "{}",
+            json!({
+                "logical_path": file.logical_path,
+                "path": target.display().to_string(),
+                "digest": file.digest,
+            })
+        );
     }
+    Ok(())
+}
 
-    if let Some(ns) = namespace {
-        let ns_json = to_string(ns).unwrap();
+fn run_diff(args: DiffCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
+
+    let diff = diff_versions(p, &args.from, &args.to)?;
+
+    if args.json {
+        let mut doc = version_diff_to_json(&diff);
+        doc["from"] = json!(args.from);
+        doc["to"] = json!(args.to);
+        println!("{doc}");
+        return Ok(());
+    }
+
+    for change in &diff.added {
+        println!("A  {}", change.path);
+    }
+    for change in &diff.removed {
+        println!("D  {}", change.path);
+    }
+    for modification in &diff.modified {
+        println!("M  {}", modification.path);
+    }
+    for rename in &diff.renamed {
+        println!("R  {} -> {}", rename.from, rename.to);
+    }
+    Ok(())
+}
+
+fn run_history(args: HistoryCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
+
+    for summary in object_history(p)?.iter().rev() {
+        if args.json {
+            let user = summary
+                .user
+                .as_ref()
+                .map(|u| json!({"name": u.name, "address": u.address}));
+            println!(
+                "{}",
+                json!({
+                    "version": summary.version,
+                    "created": summary.created,
+                    "message": summary.message,
+                    "user": user,
+                    "added": summary.added,
+                    "removed": summary.removed,
+                    "modified": summary.modified,
+                    "renamed": summary.renamed,
+                })
+            );
+            continue;
+        }
+
+        println!("version {}", summary.version);
+        if let Some(user) = &summary.user {
+            match &user.address {
+                Some(address) => println!("Author:  {} <{address}>", user.name),
+                None => println!("Author:  {}", user.name),
+            }
+        }
+        println!("Date:    {}", summary.created);
+        println!(
+            "Changes: {} added, {} removed, {} modified, {} renamed",
+            summary.added, summary.removed, summary.modified, summary.renamed
+        );
+        if let Some(message) = &summary.message {
+            println!();
+            for line in message.lines() {
+                println!("    {line}");
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+fn run_du(args: DuCmd) -> Result<()> {
+    for path in &args.paths {
+        if is_object_root(path) {
+            println!("{}", usage_to_json(&object_usage(path)?, true));
+        } else if is_storage_root(path) {
+            let mut usage = storage_root_usage(path);
+            for e in &usage.errors {
+                eprintln!("{e}");
+            }
+            let objects = usage.objects.len();
+
+            if let Some(n) = args.top {
+                usage.objects.sort_by_key(|object| Reverse(object.bytes));
+                usage.objects.truncate(n);
+            }
+            for object in &usage.objects {
+                println!("{}", usage_to_json(object, args.versions));
+            }
+            println!(
+                "{}",
+                json!({
+                    "storage_root": path,
+                    "objects": objects,
+                    "files": usage.files,
+                    "bytes": usage.bytes,
+                })
+            );
+        } else {
+            let abs_path = Path::new(path).canonicalize()?;
+            let path_str = abs_path.display().to_string();
+            eprintln!("{path_str} is not a storage root");
+        }
+    }
+
+    Ok(())
+}
+
+fn run_duplicates(args: DuplicatesCmd) -> Result<()> {
+    let mut roots = Vec::new();
+    for path in &args.paths {
+        if is_storage_root(path) {
+            roots.push(path.as_str());
+        } else {
+            let abs_path = Path::new(path).canonicalize()?;
+            let path_str = abs_path.display().to_string();
+            eprintln!("{path_str} is not a storage root");
+        }
+    }
+
+    let duplicates = find_duplicates(&roots);
+    for e in &duplicates.errors {
+        eprintln!("{e}");
+    }
+    for group in &duplicates.groups {
+        let objects = group
+            .objects
+            .iter()
+            .map(|o| {
+                json!({
+                    "path": o.path.display().to_string(),
+                    "head": o.head,
+                    "inventory_digest": o.inventory_digest,
+                })
+            })
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "id": group.id,
+                "objects": objects,
+                "heads_match": group.heads_match(),
+                "inventories_match": group.inventories_match(),
+            })
+        );
+    }
+
+    Ok(())
+}
+
+fn run_analyze_dedup(args: AnalyzeDedupCmd) -> Result<()> {
+    let mut roots = Vec::new();
+    for path in &args.paths {
+        if is_storage_root(path) {
+            roots.push(path.as_str());
+        } else {
+            let abs_path = Path::new(path).canonicalize()?;
+            let path_str = abs_path.display().to_string();
+            eprintln!("{path_str} is not a storage root");
+        }
+    }
+
+    let report = analyze_dedup(&roots);
+    for e in &report.errors {
+        eprintln!("{e}");
+    }
+
+    let limit = args.top.unwrap_or(report.shared.len());
+    for shared in report.shared.iter().take(limit) {
+        let objects = shared
+            .objects
+            .iter()
+            .map(|o| o.display().to_string())
+            .collect::<Vec<_>>();
+        println!(
+            "{}",
+            json!({
+                "algorithm": shared.algorithm,
+                "digest": shared.digest,
+                "size": shared.size,
+                "copies": shared.copies,
+                "duplicated_bytes": shared.duplicated_bytes(),
+                "objects": objects,
+            })
+        );
+    }
+    println!(
+        "{}",
+        json!({
+            "objects": report.objects,
+            "unique_contents": report.unique_contents,
+            "shared_contents": report.shared.len(),
+            "total_bytes": report.total_bytes,
+            "unique_bytes": report.unique_bytes,
+            "duplicated_bytes": report.duplicated_bytes(),
+        })
+    );
+
+    Ok(())
+}
+
+fn run_fixity(args: FixityCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
+
+    let mut webhook = args.post.webhook()?;
+    let issues = check_fixity(p)?;
+    for issue in &issues {
+        emit(fixity_issue_to_json(issue), &mut webhook)?;
+    }
+    if let Some(webhook) = &mut webhook {
+        webhook.flush()?;
+    }
+
+    if !issues.is_empty() {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!("{} failed fixity check", abs.display())));
+    }
+    Ok(())
+}
+
+fn run_compare(args: CompareCmd) -> Result<()> {
+    for root in [&args.root_a, &args.root_b] {
+        if !is_storage_root(root) {
+            let abs = Path::new(root)
+                .canonicalize()
+                .unwrap_or_else(|_| Path::new(root).to_path_buf());
+            return Err(anyhow!(format!("{} is not a storage root", abs.display())));
+        }
+    }
+
+    let comparison = compare_roots(&args.root_a, &args.root_b);
+    for e in &comparison.errors {
+        eprintln!("{e}");
+    }
+
+    for (status, records) in [
+        ("missing_in_a", &comparison.missing_in_a),
+        ("missing_in_b", &comparison.missing_in_b),
+    ] {
+        for record in records {
+            println!(
+                "{}",
+                json!({
+                    "status": status,
+                    "id": record.id,
+                    "path": record.path.display().to_string(),
+                })
+            );
+        }
+    }
+
+    let mut fixity_failures = 0;
+    let mut fixity_errors = 0;
+    let mut content_differs = 0;
+    for pair in &comparison.pairs {
+        let status = if !pair.heads_match() {
+            Some("head_differs")
+        } else if !pair.inventories_match() {
+            Some("inventory_differs")
+        } else {
+            None
+        };
+        if let Some(status) = status {
+            println!(
+                "{}",
+                json!({
+                    "status": status,
+                    "id": pair.a.id,
+                    "a": {"path": pair.a.path.display().to_string(), "head": pair.a.head},
+                    "b": {"path": pair.b.path.display().to_string(), "head": pair.b.head},
+                })
+            );
+        }
+
+        if args.fixity {
+            let (issues_a, issues_b) = compare_fixity(pair);
+            for (side, record, issues) in [("a", &pair.a, issues_a), ("b", &pair.b, issues_b)] {
+                // One unreadable copy must not end the audit of the others.
+                let issues = match issues {
+                    Ok(issues) => issues,
+                    Err(e) => {
+                        fixity_errors += 1;
+                        println!(
+                            "{}",
+                            json!({
+                                "status": "fixity_error",
+                                "id": record.id,
+                                "side": side,
+                                "object": record.path.display().to_string(),
+                                "error": e.to_string(),
+                            })
+                        );
+                        continue;
+                    }
+                };
+                for issue in &issues {
+                    fixity_failures += 1;
+                    let mut value = fixity_issue_to_json(issue);
+                    value["status"] = json!("fixity_failed");
+                    value["id"] = json!(record.id);
+                    value["side"] = json!(side);
+                    value["object"] = json!(record.path.display().to_string());
+                    println!("{value}");
+                }
+            }
+
+            match compare_content(pair) {
+                Ok(differences) => {
+                    for difference in &differences {
+                        content_differs += 1;
+                        println!(
+                            "{}",
+                            json!({
+                                "status": "content_differs",
+                                "id": pair.a.id,
+                                "path": difference.path,
+                                "algorithm": difference.algorithm,
+                                "a": difference.a,
+                                "b": difference.b,
+                            })
+                        );
+                    }
+                }
+                Err(e) => {
+                    fixity_errors += 1;
+                    println!(
+                        "{}",
+                        json!({"status": "fixity_error", "id": pair.a.id, "error": e.to_string()})
+                    );
+                }
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        json!({
+            "matched": comparison.pairs.len(),
+            "missing_in_a": comparison.missing_in_a.len(),
+            "missing_in_b": comparison.missing_in_b.len(),
+            "head_differs": comparison.pairs.iter().filter(|p| !p.heads_match()).count(),
+            "inventory_differs": comparison.pairs.iter().filter(|p| !p.inventories_match()).count(),
+            "fixity_failures": fixity_failures,
+            "fixity_errors": fixity_errors,
+            "content_differs": content_differs,
+        })
+    );
+
+    if !comparison.is_consistent() || fixity_failures + fixity_errors + content_differs > 0 {
+        return Err(anyhow!("storage roots differ"));
+    }
+    Ok(())
+}
+
+fn run_index(args: IndexCmd) -> Result<()> {
+    let mut index = Index::open(&args.db)?;
+
+    for path in &args.paths {
+        if is_storage_root(path) && args.incremental {
+            let feed = index.update_storage_root(path)?;
+            for e in &feed.errors {
+                eprintln!("{e}");
+            }
+            for event in &feed.events {
+                println!("{}", change_event_to_json(event));
+            }
+        } else if is_storage_root(path) {
+            let report = index.index_storage_root(path)?;
+            for e in &report.errors {
+                eprintln!("{e}");
+            }
+            println!(
+                "{}",
+                json!({
+                    "storage_root": path,
+                    "indexed": report.indexed,
+                    "removed": report.removed,
+                    "errors": report.errors.len(),
+                })
+            );
+        } else {
+            let abs_path = Path::new(path).canonicalize()?;
+            let path_str = abs_path.display().to_string();
+            eprintln!("{path_str} is not a storage root");
+        }
+    }
+
+    Ok(())
+}
+
+fn run_query(args: QueryCmd) -> Result<()> {
+    if !Path::new(&args.db).is_file() {
+        return Err(anyhow!(format!(
+            "index database {} does not exist",
+            args.db
+        )));
+    }
+
+    let index = Index::open(&args.db)?;
+    let storage_root = match &args.storage_root {
+        Some(root) => Some(Path::new(root).canonicalize()?),
+        None => None,
+    };
+    let query = Query {
+        id: args.id,
+        storage_root,
+        spec_version: args.spec_version,
+        head: args.head,
+        created_after: args.created_after,
+        created_before: args.created_before,
+    };
+
+    for entry in index.query(&query)? {
+        println!("{}", index_entry_to_json(&entry));
+    }
+    Ok(())
+}
+
+fn run_serve(args: ServeCmd) -> Result<()> {
+    if !is_storage_root(&args.path) {
+        let abs_path = Path::new(&args.path).canonicalize()?;
+        return Err(anyhow!(format!(
+            "{} is not a storage root",
+            abs_path.display()
+        )));
+    }
+
+    let server = Server::bind(&args.path, &args.listen)?;
+    for e in server.crawl_errors() {
+        eprintln!("{e}");
+    }
+    if let Some(addr) = server.local_addr() {
+        eprintln!("Serving {} on http://{addr}", args.path);
+    }
+    server.run()?;
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn run_watch(args: WatchCmd) -> Result<()> {
+    if !is_storage_root(&args.path) {
+        let abs_path = Path::new(&args.path).canonicalize()?;
+        return Err(anyhow!(format!(
+            "{} is not a storage root",
+            abs_path.display()
+        )));
+    }
+
+    let mut webhook = args.post.webhook()?;
+    let mut watcher = Watcher::new(&args.path)?;
+    let mut stdout = io::stdout();
+    // Unlike the one-shot commands, the watcher outlives an unreachable endpoint: failed
+    // deliveries are logged and their records stay queued for the next attempt.
+    let mut dropped = 0;
+    loop {
+        for event in watcher.wait()? {
+            let record = change_event_to_json(&event);
+            println!("{record}");
+            if let Some(webhook) = &mut webhook {
+                log_delivery_failure(webhook.push(record));
+            }
+        }
+        stdout.flush()?;
+        if let Some(webhook) = &mut webhook {
+            log_delivery_failure(webhook.flush());
+            if webhook.dropped() > dropped {
+                eprintln!(
+                    "webhook queue full, dropped {} oldest records",
+                    webhook.dropped() - dropped
+                );
+                dropped = webhook.dropped();
+            }
+        }
+    }
+}
+
+fn run_create(args: CreateCmd) -> Result<()> {
+    let root = Path::new(&args.root);
+    if !is_storage_root(root) {
+        return Err(anyhow!(format!(
+            "{} is not a storage root",
+            display_location(&args.root)
+        )));
+    }
+
+    let object = create_object(
+        root,
+        &args.id,
+        &args.source,
+        args.digest_algorithm,
+        &args.version.info(),
+    )?;
+    let inventory = read_object_inventory(&object)?;
+    let files: usize = inventory.version(None)?.state.values().map(Vec::len).sum();
+    println!(
+        "{}",
+        json!({
+            "id": inventory.id,
+            "path": object.display().to_string(),
+            "head": inventory.head,
+            "digest_algorithm": inventory.digest_algorithm,
+            "files": files,
+        })
+    );
+    Ok(())
+}
+
+fn run_commit(args: CommitCmd) -> Result<()> {
+    let p = Path::new(&args.path);
+    if !is_object_root(p) {
+        let abs = p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+        return Err(anyhow!(format!(
+            "{} is not an OCFL object root",
+            abs.display()
+        )));
+    }
+
+    let commit = commit_version(p, &args.source, &args.version.info())?;
+    let mut doc = version_diff_to_json(&commit.diff);
+    doc["version"] = json!(commit.version);
+    println!("{doc}");
+    Ok(())
+}
+
+fn run_init_root(args: InitRootCmd) -> Result<()> {
+    let mut config = serde_json::Map::new();
+    if let Some(algorithm) = &args.digest_algorithm {
+        config.insert(String::from("digestAlgorithm"), json!(algorithm));
+    }
+    if let Some(tuple_size) = args.tuple_size {
+        config.insert(String::from("tupleSize"), json!(tuple_size));
+    }
+    if let Some(number_of_tuples) = args.number_of_tuples {
+        config.insert(String::from("numberOfTuples"), json!(number_of_tuples));
+    }
+    if args.short_object_root {
+        config.insert(String::from("shortObjectRoot"), json!(true));
+    }
+    if let Some(delimiter) = &args.delimiter {
+        config.insert(String::from("delimiter"), json!(delimiter));
+    }
+
+    let layout = Layout::from_config(&args.layout, Some(&serde_json::Value::Object(config)))?;
+    init_storage_root(&args.path, &args.spec, &layout)?;
+    println!(
+        "{}",
+        json!({
+            "path": display_location(&args.path),
+            "spec": args.spec,
+            "layout": layout.to_config(),
+        })
+    );
+    Ok(())
+}
+
+fn run_extensions(args: ExtensionsCmd) -> Result<()> {
+    for path in &args.paths {
+        let issues = if is_object_root(path) {
+            let (names, issues) = object_extensions(path)?;
+            println!("{}", extensions_to_json(Path::new(path), &names));
+            issues
+        } else if is_storage_root(path) {
+            let report = storage_root_extensions(path)?;
+            for e in &report.errors {
+                eprintln!("{e}");
+            }
+            for (object, names) in &report.objects {
+                println!("{}", extensions_to_json(object, names));
+            }
+            println!(
+                "{}",
+                json!({
+                    "storage_root": path,
+                    "extensions": report.root,
+                    "counts": report.counts,
+                })
+            );
+            report.issues
+        } else {
+            let abs_path = Path::new(path).canonicalize()?;
+            let path_str = abs_path.display().to_string();
+            eprintln!("{path_str} is not a storage root");
+            continue;
+        };
+
+        for issue in &issues {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!(
+                "{}",
+                json!({"code": issue.code, "severity": severity, "message": issue.message})
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `location` for messages: remote URLs as given, local paths made absolute.
+fn display_location(location: &str) -> String {
+    if is_remote(location) {
+        return location.to_string();
+    }
+    let p = Path::new(location);
+    p.canonicalize()
+        .unwrap_or_else(|_| p.to_path_buf())
+        .display()
+        .to_string()
+}
+
+/// Prints `record` and queues it for delivery when `--post-to` was given.
+fn emit(record: serde_json::Value, webhook: &mut Option<Webhook>) -> Result<()> {
+    println!("{record}");
+    if let Some(webhook) = webhook {
+        webhook.push(record)?;
+    }
+    Ok(())
+}
+
+fn log_delivery_failure(result: io::Result<()>) {
+    // A paused webhook is still waiting out the backoff of a failure that was already logged.
+    if let Err(e) = result.or_else(|e| match e.kind() {
+        io::ErrorKind::WouldBlock => Ok(()),
+        _ => Err(e),
+    }) {
+        eprintln!("webhook delivery failed, keeping records queued: {e}");
+    }
+}
+
+fn change_event_to_json(event: &ChangeEvent) -> serde_json::Value {
+    json!({
+        "event": event.kind.as_str(),
+        "path": event.path.display().to_string(),
+        "id": event.id,
+        "head": event.head,
+    })
+}
+
+fn version_diff_to_json(diff: &VersionDiff) -> serde_json::Value {
+    let changes = |list: &[FileChange]| {
+        list.iter()
+            .map(|c| json!({"path": c.path, "digest": c.digest}))
+            .collect::<Vec<_>>()
+    };
+    let modified = diff
+        .modified
+        .iter()
+        .map(|m| {
+            json!({
+                "path": m.path,
+                "old_digest": m.old_digest,
+                "new_digest": m.new_digest,
+            })
+        })
+        .collect::<Vec<_>>();
+    let renamed = diff
+        .renamed
+        .iter()
+        .map(|r| json!({"from": r.from, "to": r.to, "digest": r.digest}))
+        .collect::<Vec<_>>();
+    json!({
+        "added": changes(&diff.added),
+        "removed": changes(&diff.removed),
+        "modified": modified,
+        "renamed": renamed,
+    })
+}
+
+fn extensions_to_json(path: &Path, names: &[String]) -> serde_json::Value {
+    let extensions = names
+        .iter()
+        .map(|name| json!({"name": name, "registered": is_registered_extension(name)}))
+        .collect::<Vec<_>>();
+    json!({
+        "path": path.display().to_string(),
+        "extensions": extensions,
+    })
+}
+
+fn index_entry_to_json(entry: &IndexEntry) -> serde_json::Value {
+    json!({
+        "path": entry.path.display().to_string(),
+        "storage_root": entry.storage_root.display().to_string(),
+        "id": entry.id,
+        "spec_version": entry.spec_version,
+        "head": entry.head,
+        "version_count": entry.version_count,
+        "first_created": entry.first_created,
+        "last_created": entry.last_created,
+        "logical_bytes": entry.logical_bytes,
+        "physical_bytes": entry.physical_bytes,
+        "inventory_digest": entry.inventory_digest,
+    })
+}
+
+fn fixity_issue_to_json(issue: &FixityIssue) -> serde_json::Value {
+    json!({
+        "path": issue.path,
+        "algorithm": issue.algorithm,
+        "expected": issue.expected,
+        "actual": issue.actual,
+    })
+}
+
+fn usage_to_json(usage: &ObjectUsage, versions: bool) -> serde_json::Value {
+    let mut value = json!({
+        "path": usage.path.display().to_string(),
+        "id": usage.id,
+        "files": usage.files,
+        "bytes": usage.bytes,
+        "logical_bytes": usage.logical_bytes,
+    });
+    if !usage.missing.is_empty() {
+        value["missing_files"] = json!(usage.missing);
+    }
+    if versions {
+        value["versions"] = usage
+            .versions
+            .iter()
+            .map(|v| {
+                json!({
+                    "version": v.version,
+                    "new_files": v.new_files,
+                    "new_bytes": v.new_bytes,
+                    "carried_files": v.carried_files,
+                    "carried_bytes": v.carried_bytes,
+                    "logical_files": v.logical_files,
+                    "logical_bytes": v.logical_bytes,
+                })
+            })
+            .collect();
+    }
+    value
+}
+
+pub fn object_to_json<P: AsRef<Path>>(
+    path: P,
+    absolute: bool,
+    key: bool,
+    identifier: bool,
+    namespace: Option<&str>,
+) -> String {
+    let path_ref = path.as_ref();
+
+    // Decide whether to emit an absolute or as-given path.
+    let path_str = if absolute {
+        path_ref
+            .canonicalize()
+            .unwrap_or_else(|_| path_ref.to_path_buf())
+            .display()
+            .to_string()
+    } else {
+        path_ref.display().to_string()
+    };
+
+    let id = identifier.then(|| get_object_id(path_ref).unwrap_or_default());
+    let key = key.then(|| {
+        path_ref
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string()
+    });
+
+    let mutable_head = mutable_head_to_json(read_mutable_head(path_ref));
+    object_fields_to_json(&path_str, id, key, namespace, mutable_head)
+}
+
+/// Like `object_to_json`, for the object at `object` within `storage`, which was opened from
+/// `location`.
+fn object_in_to_json(
+    storage: &dyn Storage,
+    location: &str,
+    object: &str,
+    key: bool,
+    identifier: bool,
+    namespace: Option<&str>,
+) -> String {
+    let path_str = format!("{}/{object}", location.trim_end_matches('/'));
+    let id = identifier.then(|| get_object_id_in(storage, object).unwrap_or_default());
+    let key = key.then(|| object.rsplit('/').next().unwrap_or(object).to_string());
+    let mutable_head = mutable_head_to_json(read_mutable_head_in(storage, object));
+    object_fields_to_json(&path_str, id, key, namespace, mutable_head)
+}
+
+/// Only objects using the 0005-mutable-head extension get the extra key; a head that cannot
+/// be read is reported rather than hidden.
+fn mutable_head_to_json(head: io::Result<Option<MutableHead>>) -> Option<serde_json::Value> {
+    match head {
+        Ok(Some(head)) => Some(json!({"version": head.version, "revision": head.revision})),
+        Ok(None) => None,
+        Err(e) => Some(json!({"error": e.to_string()})),
+    }
+}
+
+fn object_fields_to_json(
+    path: &str,
+    id: Option<String>,
+    key: Option<String>,
+    namespace: Option<&str>,
+    mutable_head: Option<serde_json::Value>,
+) -> String {
+    // Build JSON manually to control key order: path, id, key, namespace, mutable_head.
+    let mut parts: Vec<String> = Vec::new();
+
+    // Always include the path first.
+    let path_json = to_string(path).unwrap();
+    parts.push(format!("\"path\":{path_json}"));
+
+    if let Some(id) = id {
+        let id_json = to_string(&id).unwrap();
+        parts.push(format!("\"id\":{id_json}"));
+    }
+
+    if let Some(key) = key {
+        let key_json = to_string(&key).unwrap();
+        parts.push(format!("\"key\":{key_json}"));
+    }
+
+    if let Some(ns) = namespace {
+        let ns_json = to_string(ns).unwrap();
         parts.push(format!("\"namespace\":{ns_json}"));
     }
 
+    if let Some(head) = mutable_head {
+        parts.push(format!("\"mutable_head\":{head}"));
+    }
+
     format!("{{{}}}", parts.join(","))
 }
 
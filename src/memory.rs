@@ -0,0 +1,427 @@
+use crate::backend::{DirEntry, EntryKind, Metadata, Storage, join_path};
+use crate::digest::{DigestAlgorithm, hex_digest};
+use crate::inventory::{DigestMap, Inventory, User, Version};
+use std::{
+    collections::{BTreeMap, btree_map},
+    io::{Cursor, Error, ErrorKind, Read},
+};
+
+/// Timestamp recorded for versions built without an explicit `created` value, so that built
+/// objects are reproducible.
+pub const DEFAULT_CREATED: &str = "2020-01-01T00:00:00Z";
+
+/// A content path and the bytes stored there.
+type ContentFile = (String, Vec<u8>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entry {
+    File(Vec<u8>),
+    Dir,
+}
+
+/// Storage held entirely in memory, for tests and for embedding the crawler without a
+/// filesystem.
+///
+/// Files are inserted with `insert_file`, which creates missing parent directories. Use
+/// `StorageRootBuilder` and `ObjectBuilder` to synthesize complete storage roots and objects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryStorage {
+    entries: BTreeMap<String, Entry>,
+}
+
+impl Default for MemoryStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryStorage {
+    /// Creates a storage holding only its (empty) base directory.
+    pub fn new() -> Self {
+        let mut entries = BTreeMap::new();
+        entries.insert(String::new(), Entry::Dir);
+        Self { entries }
+    }
+
+    /// Writes `contents` to the file at `path`, replacing any previous contents.
+    pub fn insert_file(&mut self, path: &str, contents: impl Into<Vec<u8>>) {
+        let path = normalize(path);
+        self.insert_parents(&path);
+        self.entries.insert(path, Entry::File(contents.into()));
+    }
+
+    /// Creates the directory at `path` and any missing parents.
+    pub fn create_dir(&mut self, path: &str) {
+        let path = normalize(path);
+        self.insert_parents(&path);
+        self.entries.entry(path).or_insert(Entry::Dir);
+    }
+
+    /// Removes the entry at `path` and, for a directory, everything below it. Returns false if
+    /// nothing was there.
+    pub fn remove(&mut self, path: &str) -> bool {
+        let path = normalize(path);
+        if path.is_empty() {
+            let removed = self.entries.len() > 1;
+            *self = Self::new();
+            return removed;
+        }
+        let removed = self.entries.remove(&path).is_some();
+        let prefix = format!("{path}/");
+        self.entries.retain(|name, _| !name.starts_with(&prefix));
+        removed
+    }
+
+    fn insert_parents(&mut self, path: &str) {
+        let mut parent = path;
+        while let Some((dir, _)) = parent.rsplit_once('/') {
+            self.entries.entry(dir.to_string()).or_insert(Entry::Dir);
+            parent = dir;
+        }
+    }
+
+    fn entry(&self, path: &str) -> Result<&Entry, Error> {
+        self.entries.get(&normalize(path)).ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("{path} not found in memory storage"),
+            )
+        })
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, Error> {
+        if self.entry(path)? != &Entry::Dir {
+            return Err(Error::new(
+                ErrorKind::NotADirectory,
+                format!("{path} is not a directory"),
+            ));
+        }
+
+        let dir = normalize(path);
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{dir}/")
+        };
+        Ok(self
+            .entries
+            .iter()
+            .filter_map(|(name, entry)| {
+                let rest = name.strip_prefix(&prefix)?;
+                if rest.is_empty() || rest.contains('/') {
+                    return None;
+                }
+                Some(DirEntry {
+                    name: rest.to_string(),
+                    kind: match entry {
+                        Entry::Dir => EntryKind::Dir,
+                        Entry::File(_) => EntryKind::File,
+                    },
+                })
+            })
+            .collect())
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + '_>, Error> {
+        match self.entry(path)? {
+            Entry::File(contents) => Ok(Box::new(Cursor::new(contents.as_slice()))),
+            Entry::Dir => Err(Error::new(
+                ErrorKind::IsADirectory,
+                format!("{path} is a directory"),
+            )),
+        }
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata, Error> {
+        Ok(match self.entry(path)? {
+            Entry::Dir => Metadata {
+                kind: EntryKind::Dir,
+                size: 0,
+            },
+            Entry::File(contents) => Metadata {
+                kind: EntryKind::File,
+                size: contents.len() as u64,
+            },
+        })
+    }
+}
+
+/// Builds an in-memory OCFL storage root holding objects built by `ObjectBuilder`.
+#[derive(Debug, Clone)]
+pub struct StorageRootBuilder {
+    spec_version: String,
+    objects: Vec<(String, ObjectBuilder)>,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl Default for StorageRootBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StorageRootBuilder {
+    /// Starts an OCFL 1.1 storage root.
+    pub fn new() -> Self {
+        Self {
+            spec_version: String::from("1.1"),
+            objects: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Sets the OCFL version of the storage root marker ("1.0" or "1.1").
+    pub fn spec_version(mut self, spec_version: &str) -> Self {
+        self.spec_version = spec_version.to_string();
+        self
+    }
+
+    /// Adds `object` at `path` below the storage root.
+    pub fn object(mut self, path: &str, object: ObjectBuilder) -> Self {
+        self.objects.push((path.to_string(), object));
+        self
+    }
+
+    /// Adds an arbitrary file, such as `ocfl_layout.json` or a stray file to crawl past.
+    pub fn file(mut self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        self.files.push((path.to_string(), contents.into()));
+        self
+    }
+
+    /// Builds the storage root at the base of a new `MemoryStorage`.
+    pub fn build(&self) -> Result<MemoryStorage, Error> {
+        let mut storage = MemoryStorage::new();
+        storage.insert_file(
+            &format!("0=ocfl_{}", self.spec_version),
+            format!("ocfl_{}\n", self.spec_version),
+        );
+        for (path, contents) in &self.files {
+            storage.insert_file(path, contents.clone());
+        }
+        for (path, object) in &self.objects {
+            object.write_to(&mut storage, path)?;
+        }
+        Ok(storage)
+    }
+}
+
+/// Builds an OCFL object: its namaste marker, content, root and per-version inventories and
+/// their sidecars.
+///
+/// Each version lists the complete logical state of that version. Content already present in
+/// the manifest is not stored again, as in real OCFL objects.
+#[derive(Debug, Clone)]
+pub struct ObjectBuilder {
+    id: String,
+    spec_version: String,
+    digest_algorithm: DigestAlgorithm,
+    content_directory: Option<String>,
+    versions: Vec<VersionBuilder>,
+    sidecars: bool,
+    version_inventories: bool,
+}
+
+impl ObjectBuilder {
+    /// Starts an OCFL 1.1 object with identifier `id`, using sha512 digests.
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            spec_version: String::from("1.1"),
+            digest_algorithm: DigestAlgorithm::Sha512,
+            content_directory: None,
+            versions: Vec::new(),
+            sidecars: true,
+            version_inventories: true,
+        }
+    }
+
+    /// Sets the OCFL version of the object ("1.0" or "1.1").
+    pub fn spec_version(mut self, spec_version: &str) -> Self {
+        self.spec_version = spec_version.to_string();
+        self
+    }
+
+    /// Sets the digest algorithm; anything but sha512 or sha256 makes the object invalid.
+    pub fn digest_algorithm(mut self, algorithm: DigestAlgorithm) -> Self {
+        self.digest_algorithm = algorithm;
+        self
+    }
+
+    /// Stores content under `content_directory` instead of "content" and declares it in the
+    /// inventory.
+    pub fn content_directory(mut self, content_directory: &str) -> Self {
+        self.content_directory = Some(content_directory.to_string());
+        self
+    }
+
+    /// Appends the next version.
+    pub fn version(mut self, version: VersionBuilder) -> Self {
+        self.versions.push(version);
+        self
+    }
+
+    /// Leaves out the `inventory.json.<algorithm>` sidecars.
+    pub fn without_sidecars(mut self) -> Self {
+        self.sidecars = false;
+        self
+    }
+
+    /// Leaves out the inventory copies inside version directories.
+    pub fn without_version_inventories(mut self) -> Self {
+        self.version_inventories = false;
+        self
+    }
+
+    /// Returns the root inventory the object will be written with.
+    pub fn inventory(&self) -> Result<Inventory, Error> {
+        Ok(self
+            .build_versions()?
+            .pop()
+            .map(|(inventory, _)| inventory)
+            .unwrap_or_else(|| self.empty_inventory()))
+    }
+
+    /// Writes the object to the directory `path` within `storage`.
+    pub fn write_to(&self, storage: &mut MemoryStorage, path: &str) -> Result<(), Error> {
+        storage.insert_file(
+            &join_path(path, &format!("0=ocfl_object_{}", self.spec_version)),
+            format!("ocfl_object_{}\n", self.spec_version),
+        );
+
+        let mut root_inventory = self.empty_inventory();
+        for (inventory, content) in self.build_versions()? {
+            let version_dir = join_path(path, &inventory.head);
+            storage.create_dir(&version_dir);
+            for (content_path, contents) in content {
+                storage.insert_file(&join_path(path, &content_path), contents);
+            }
+            if self.version_inventories {
+                self.write_inventory(storage, &version_dir, &inventory)?;
+            }
+            root_inventory = inventory;
+        }
+        self.write_inventory(storage, path, &root_inventory)
+    }
+
+    fn write_inventory(
+        &self,
+        storage: &mut MemoryStorage,
+        dir: &str,
+        inventory: &Inventory,
+    ) -> Result<(), Error> {
+        let json = inventory.to_json();
+        if self.sidecars {
+            let digest = hex_digest(self.digest_algorithm, json.as_bytes())?;
+            storage.insert_file(
+                &join_path(dir, &format!("inventory.json.{}", self.digest_algorithm)),
+                format!("{digest} inventory.json\n"),
+            );
+        }
+        storage.insert_file(&join_path(dir, "inventory.json"), json);
+        Ok(())
+    }
+
+    fn empty_inventory(&self) -> Inventory {
+        Inventory {
+            id: self.id.clone(),
+            type_: format!("https://ocfl.io/{}/spec/#inventory", self.spec_version),
+            digest_algorithm: self.digest_algorithm.to_string(),
+            head: String::new(),
+            content_directory: self.content_directory.clone(),
+            manifest: DigestMap::new(),
+            versions: BTreeMap::new(),
+            fixity: BTreeMap::new(),
+        }
+    }
+
+    /// Returns, for each version, the inventory as of that version and the content it adds,
+    /// keyed by content path.
+    fn build_versions(&self) -> Result<Vec<(Inventory, Vec<ContentFile>)>, Error> {
+        let mut inventory = self.empty_inventory();
+        let content_directory = inventory.content_directory().to_string();
+        let mut built = Vec::new();
+
+        for (i, version) in self.versions.iter().enumerate() {
+            let name = format!("v{}", i + 1);
+            let mut state = DigestMap::new();
+            let mut content = Vec::new();
+            for (logical_path, contents) in &version.files {
+                let digest = hex_digest(self.digest_algorithm, contents.as_slice())?;
+                state
+                    .entry(digest.clone())
+                    .or_default()
+                    .push(logical_path.clone());
+                if let btree_map::Entry::Vacant(entry) = inventory.manifest.entry(digest) {
+                    let content_path = format!("{name}/{content_directory}/{logical_path}");
+                    entry.insert(vec![content_path.clone()]);
+                    content.push((content_path, contents.clone()));
+                }
+            }
+
+            inventory.versions.insert(
+                name.clone(),
+                Version {
+                    created: version
+                        .created
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_CREATED.to_string()),
+                    message: version.message.clone(),
+                    user: version.user.clone(),
+                    state,
+                },
+            );
+            inventory.head = name;
+            built.push((inventory.clone(), content));
+        }
+        Ok(built)
+    }
+}
+
+/// The logical state and metadata of one version built by `ObjectBuilder`.
+#[derive(Debug, Clone, Default)]
+pub struct VersionBuilder {
+    files: BTreeMap<String, Vec<u8>>,
+    created: Option<String>,
+    message: Option<String>,
+    user: Option<User>,
+}
+
+impl VersionBuilder {
+    /// Starts an empty version.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the file `logical_path` with `contents` to the version's state.
+    pub fn file(mut self, logical_path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        self.files.insert(logical_path.to_string(), contents.into());
+        self
+    }
+
+    /// Sets the `created` timestamp (default `DEFAULT_CREATED`).
+    pub fn created(mut self, created: &str) -> Self {
+        self.created = Some(created.to_string());
+        self
+    }
+
+    /// Sets the version message.
+    pub fn message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    /// Sets the user who created the version.
+    pub fn user(mut self, name: &str, address: Option<&str>) -> Self {
+        self.user = Some(User {
+            name: name.to_string(),
+            address: address.map(String::from),
+        });
+        self
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.trim_matches('/').to_string()
+}
@@ -0,0 +1,113 @@
+use crate::backend::{LocalStorage, Storage, join_path};
+use crate::export::export_files;
+use crate::inventory::{Inventory, read_object_inventory_in};
+use crate::logical::{LogicalFile, inventory_logical_files};
+use crate::object::is_object_root_in;
+use std::{
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Name of the extension that keeps an uncommitted version inside an object.
+pub const MUTABLE_HEAD_EXTENSION: &str = "0005-mutable-head";
+
+/// The uncommitted version kept by the `0005-mutable-head` extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutableHead {
+    /// Version the mutable head becomes once committed, e.g. "v3" for an object at "v2".
+    pub version: String,
+    /// Highest revision recorded for the mutable head, if any.
+    pub revision: Option<u32>,
+    /// The mutable head's inventory; its manifest paths are relative to the object root.
+    pub inventory: Inventory,
+}
+
+/// Directory holding the mutable head's inventory and content in the object at `path`.
+pub fn mutable_head_dir<P: AsRef<Path>>(path: P) -> PathBuf {
+    path.as_ref()
+        .join("extensions")
+        .join(MUTABLE_HEAD_EXTENSION)
+        .join("head")
+}
+
+/// Returns the mutable head of the OCFL object at `path`, or `None` if it has none.
+pub fn read_mutable_head<P: AsRef<Path>>(path: P) -> Result<Option<MutableHead>, Error> {
+    read_mutable_head_in(&LocalStorage::new(path), "")
+}
+
+/// Like `read_mutable_head`, for the object root at `path` within `storage`.
+pub fn read_mutable_head_in(
+    storage: &dyn Storage,
+    path: &str,
+) -> Result<Option<MutableHead>, Error> {
+    if !is_object_root_in(storage, path) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let extension_dir = join_path(&join_path(path, "extensions"), MUTABLE_HEAD_EXTENSION);
+    let head_dir = join_path(&extension_dir, "head");
+    if !storage.is_file(&join_path(&head_dir, "inventory.json")) {
+        return Ok(None);
+    }
+    let inventory = read_object_inventory_in(storage, &head_dir)?;
+
+    // Revisions are marked by `revisions/rN` files; content directories `head/content/rN` count
+    // too, for writers that do not keep the markers.
+    let revision = [
+        join_path(&extension_dir, "revisions"),
+        join_path(&head_dir, inventory.content_directory()),
+    ]
+    .iter()
+    .filter_map(|dir| storage.read_dir(dir).ok())
+    .flatten()
+    .filter_map(|entry| revision_number(&entry.name))
+    .max();
+
+    Ok(Some(MutableHead {
+        version: inventory.head.clone(),
+        revision,
+        inventory,
+    }))
+}
+
+/// Returns the logical files of the mutable head of the OCFL object at `path`, sorted by
+/// logical path.
+pub fn mutable_head_files<P: AsRef<Path>>(path: P) -> Result<Vec<LogicalFile>, Error> {
+    let p = path.as_ref();
+    let head = require_mutable_head(p)?;
+    inventory_logical_files(p, &head.inventory, None)
+}
+
+/// Materializes the logical state of the mutable head of the OCFL object at `path` into
+/// `dest`, with the same checks as `export_version`.
+pub fn export_mutable_head<P: AsRef<Path>, Q: AsRef<Path>>(
+    path: P,
+    dest: Q,
+) -> Result<Vec<LogicalFile>, Error> {
+    let p = path.as_ref();
+    let head = require_mutable_head(p)?;
+    let files = inventory_logical_files(p, &head.inventory, None)?;
+    export_files(p, &files, head.inventory.algorithm()?, dest.as_ref())?;
+    Ok(files)
+}
+
+fn require_mutable_head(path: &Path) -> Result<MutableHead, Error> {
+    read_mutable_head(path)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("{} has no mutable head", path.display()),
+        )
+    })
+}
+
+/// Returns the number of a revision name such as "r2".
+fn revision_number(name: &str) -> Option<u32> {
+    let digits = name.strip_prefix('r')?;
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
@@ -1,21 +1,24 @@
-use crate::utils::is_directory;
+use crate::backend::{LocalStorage, Storage, join_path};
 use std::path::Path;
 
 /// Returns true if `path` is a directory and contains a single OCFL object root marker file
 /// ("0=ocfl_object_1.0" xor "0=ocfl_object_1.1") whose contents are "ocfl_object_1.0\n" and "ocfl_object_1.1\n" respectively,
 /// and also contains an `inventory.json` file.
 pub fn is_object_root<P: AsRef<Path>>(path: P) -> bool {
-    let p = path.as_ref();
+    is_object_root_in(&LocalStorage::new(path), "")
+}
 
-    if !is_directory(p) {
+/// Like `is_object_root`, for the directory at `path` within `storage`.
+pub fn is_object_root_in(storage: &dyn Storage, path: &str) -> bool {
+    if !storage.is_dir(path) {
         return false;
     }
 
-    let marker_10 = p.join("0=ocfl_object_1.0");
-    let marker_11 = p.join("0=ocfl_object_1.1");
+    let marker_10 = join_path(path, "0=ocfl_object_1.0");
+    let marker_11 = join_path(path, "0=ocfl_object_1.1");
 
-    let exists_10 = marker_10.is_file();
-    let exists_11 = marker_11.is_file();
+    let exists_10 = storage.is_file(&marker_10);
+    let exists_11 = storage.is_file(&marker_11);
 
     // Require exactly one marker file to be present (exclusive or).
     if exists_10 == exists_11 {
@@ -23,18 +26,17 @@ pub fn is_object_root<P: AsRef<Path>>(path: P) -> bool {
     }
 
     // Object roots must include an inventory.json file
-    let inventory = p.join("inventory.json");
-    if !inventory.is_file() {
+    if !storage.is_file(&join_path(path, "inventory.json")) {
         return false;
     }
 
     if exists_10 {
-        match std::fs::read_to_string(&marker_10) {
+        match storage.read_to_string(&marker_10) {
             Ok(contents) => contents == "ocfl_object_1.0\n",
             Err(_) => false,
         }
     } else {
-        match std::fs::read_to_string(&marker_11) {
+        match storage.read_to_string(&marker_11) {
             Ok(contents) => contents == "ocfl_object_1.1\n",
             Err(_) => false,
         }
@@ -43,17 +45,19 @@ pub fn is_object_root<P: AsRef<Path>>(path: P) -> bool {
 
 /// Returns object id from inventory.json in the OCFL object root directory.
 pub fn get_object_id<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
-    let p = path.as_ref();
+    get_object_id_in(&LocalStorage::new(path), "")
+}
 
-    if !is_object_root(p) {
+/// Like `get_object_id`, for the object root at `path` within `storage`.
+pub fn get_object_id_in(storage: &dyn Storage, path: &str) -> Result<String, std::io::Error> {
+    if !is_object_root_in(storage, path) {
         return Err(std::io::Error::new(
             std::io::ErrorKind::InvalidInput,
             "Not an OCFL object root",
         ));
     }
 
-    let inventory_path = p.join("inventory.json");
-    let inventory_content = std::fs::read_to_string(inventory_path)?;
+    let inventory_content = storage.read_to_string(&join_path(path, "inventory.json"))?;
 
     // Parse JSON and extract the "id" field safely.
     let json: serde_json::Value = serde_json::from_str(&inventory_content)
@@ -70,3 +74,27 @@ pub fn get_object_id<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error>
         "Could not find valid 'id' field in inventory.json",
     ))
 }
+
+/// Returns the OCFL specification version ("1.0" or "1.1") declared by the object root marker.
+pub fn get_object_spec_version<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
+    get_object_spec_version_in(&LocalStorage::new(path), "")
+}
+
+/// Like `get_object_spec_version`, for the object root at `path` within `storage`.
+pub fn get_object_spec_version_in(
+    storage: &dyn Storage,
+    path: &str,
+) -> Result<String, std::io::Error> {
+    if !is_object_root_in(storage, path) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    if storage.is_file(&join_path(path, "0=ocfl_object_1.0")) {
+        Ok(String::from("1.0"))
+    } else {
+        Ok(String::from("1.1"))
+    }
+}
@@ -0,0 +1,419 @@
+use crate::backend::{DirEntry, EntryKind, Metadata, Storage};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{Error, ErrorKind, Read},
+    time::Duration,
+};
+
+/// SHA-256 of an empty payload, sent with every (body-less) request.
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+/// Access keys used to sign requests with AWS Signature Version 4.
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    pub access_key: String,
+    pub secret_key: String,
+    pub session_token: Option<String>,
+}
+
+/// Storage backed by an S3-compatible object store, addressed path-style
+/// (`{endpoint}/{bucket}/{key}`) so it also works against MinIO and similar servers.
+///
+/// Directories are the common prefixes of keys, as listed by `ListObjectsV2` with a `/`
+/// delimiter. Requests are anonymous when no credentials are given.
+#[derive(Debug)]
+pub struct S3Storage {
+    endpoint: String,
+    bucket: String,
+    prefix: String,
+    region: String,
+    credentials: Option<Credentials>,
+    agent: ureq::Agent,
+}
+
+impl S3Storage {
+    /// Creates a storage for the keys below `prefix` in `bucket` at `endpoint`
+    /// (e.g. "http://127.0.0.1:9000").
+    pub fn new(
+        endpoint: &str,
+        region: &str,
+        bucket: &str,
+        prefix: &str,
+        credentials: Option<Credentials>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            region: region.to_string(),
+            credentials,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(60))
+                .build(),
+        }
+    }
+
+    /// Creates a storage from an `s3://bucket/prefix` URL.
+    ///
+    /// The endpoint comes from `AWS_ENDPOINT_URL` (defaulting to AWS for the region), the region
+    /// from `AWS_REGION` or `AWS_DEFAULT_REGION` (defaulting to us-east-1) and credentials from
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.
+    pub fn from_url(url: &str) -> Result<Self, Error> {
+        let rest = url.strip_prefix("s3://").ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{url} is not an s3:// URL"),
+            )
+        })?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{url} does not name a bucket"),
+            ));
+        }
+
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let region = env("AWS_REGION")
+            .or_else(|| env("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|| String::from("us-east-1"));
+        let endpoint =
+            env("AWS_ENDPOINT_URL").unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com"));
+        let credentials = match (env("AWS_ACCESS_KEY_ID"), env("AWS_SECRET_ACCESS_KEY")) {
+            (Some(access_key), Some(secret_key)) => Some(Credentials {
+                access_key,
+                secret_key,
+                session_token: env("AWS_SESSION_TOKEN"),
+            }),
+            _ => None,
+        };
+
+        Ok(Self::new(&endpoint, &region, bucket, prefix, credentials))
+    }
+
+    fn key(&self, path: &str) -> String {
+        let path = path.trim_matches('/');
+        match (self.prefix.is_empty(), path.is_empty()) {
+            (true, _) => path.to_string(),
+            (false, true) => self.prefix.clone(),
+            (false, false) => format!("{}/{path}", self.prefix),
+        }
+    }
+
+    /// Sends a signed request for `key` (empty for the bucket itself) with `query` parameters.
+    fn request(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+    ) -> Result<ureq::Response, Error> {
+        let mut uri = format!("/{}", uri_encode(&self.bucket, true));
+        if !key.is_empty() {
+            uri.push('/');
+            uri.push_str(&uri_encode(key, false));
+        }
+
+        let mut pairs: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (uri_encode(k, true), uri_encode(v, true)))
+            .collect();
+        pairs.sort();
+        let query_string = pairs
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let url = if query_string.is_empty() {
+            format!("{}{uri}", self.endpoint)
+        } else {
+            format!("{}{uri}?{query_string}", self.endpoint)
+        };
+
+        let mut request = self.agent.request(method, &url);
+        if let Some(credentials) = &self.credentials {
+            let host = host_of(&self.endpoint);
+            for (name, value) in sign(
+                credentials,
+                &self.region,
+                method,
+                &host,
+                &uri,
+                &query_string,
+                Utc::now(),
+            ) {
+                request = request.set(&name, &value);
+            }
+        }
+
+        match request.call() {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(404, _)) => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("s3://{}/{key} not found", self.bucket),
+            )),
+            Err(ureq::Error::Status(403, _)) => Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!("access to s3://{}/{key} denied", self.bucket),
+            )),
+            Err(ureq::Error::Status(status, _)) => Err(Error::other(format!(
+                "{method} s3://{}/{key} failed with status {status}",
+                self.bucket
+            ))),
+            Err(ureq::Error::Transport(e)) => Err(Error::other(e.to_string())),
+        }
+    }
+
+    /// Lists the keys and common prefixes directly below `prefix`, following continuation tokens.
+    fn list(&self, prefix: &str, max_keys: Option<&str>) -> Result<Vec<DirEntry>, Error> {
+        let mut entries = Vec::new();
+        let mut token: Option<String> = None;
+
+        loop {
+            let mut query = vec![("list-type", "2"), ("delimiter", "/"), ("prefix", prefix)];
+            if let Some(max_keys) = max_keys {
+                query.push(("max-keys", max_keys));
+            }
+            if let Some(token) = &token {
+                query.push(("continuation-token", token));
+            }
+
+            let body = self
+                .request("GET", "", &query)?
+                .into_string()
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            for element in elements(&body, "Contents") {
+                if let Some(key) = element_text(element, "Key") {
+                    let name = key.strip_prefix(prefix).unwrap_or(&key).to_string();
+                    if !name.is_empty() {
+                        entries.push(DirEntry {
+                            name,
+                            kind: EntryKind::File,
+                        });
+                    }
+                }
+            }
+            for element in elements(&body, "CommonPrefixes") {
+                if let Some(key) = element_text(element, "Prefix") {
+                    let name = key
+                        .strip_prefix(prefix)
+                        .unwrap_or(&key)
+                        .trim_end_matches('/')
+                        .to_string();
+                    if !name.is_empty() {
+                        entries.push(DirEntry {
+                            name,
+                            kind: EntryKind::Dir,
+                        });
+                    }
+                }
+            }
+
+            token = element_text(&body, "NextContinuationToken");
+            let truncated = element_text(&body, "IsTruncated").as_deref() == Some("true");
+            if max_keys.is_some() || !truncated || token.is_none() {
+                break;
+            }
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    fn dir_prefix(&self, path: &str) -> String {
+        let key = self.key(path);
+        if key.is_empty() {
+            key
+        } else {
+            format!("{key}/")
+        }
+    }
+}
+
+impl Storage for S3Storage {
+    fn read_dir(&self, path: &str) -> Result<Vec<DirEntry>, Error> {
+        let entries = self.list(&self.dir_prefix(path), None)?;
+        if entries.is_empty() && !self.key(path).is_empty() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("s3://{}/{} is not a directory", self.bucket, self.key(path)),
+            ));
+        }
+        Ok(entries)
+    }
+
+    fn open(&self, path: &str) -> Result<Box<dyn Read + '_>, Error> {
+        Ok(self.request("GET", &self.key(path), &[])?.into_reader())
+    }
+
+    fn metadata(&self, path: &str) -> Result<Metadata, Error> {
+        let key = self.key(path);
+        if key.is_empty() {
+            return Ok(Metadata {
+                kind: EntryKind::Dir,
+                size: 0,
+            });
+        }
+
+        match self.request("HEAD", &key, &[]) {
+            Ok(response) => {
+                return Ok(Metadata {
+                    kind: EntryKind::File,
+                    size: response
+                        .header("Content-Length")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(0),
+                });
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        // Not an object; it is a directory if any key lives below it.
+        if self.list(&self.dir_prefix(path), Some("1"))?.is_empty() {
+            Err(Error::new(
+                ErrorKind::NotFound,
+                format!("s3://{}/{key} not found", self.bucket),
+            ))
+        } else {
+            Ok(Metadata {
+                kind: EntryKind::Dir,
+                size: 0,
+            })
+        }
+    }
+}
+
+/// Returns the headers that sign a body-less request with AWS Signature Version 4.
+///
+/// `uri` must already be URI-encoded and `query` already canonical (encoded and sorted).
+pub fn sign(
+    credentials: &Credentials,
+    region: &str,
+    method: &str,
+    host: &str,
+    uri: &str,
+    query: &str,
+    now: chrono::DateTime<Utc>,
+) -> Vec<(String, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers = vec![
+        (String::from("host"), host.to_string()),
+        (
+            String::from("x-amz-content-sha256"),
+            EMPTY_SHA256.to_string(),
+        ),
+        (String::from("x-amz-date"), amz_date.clone()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push((String::from("x-amz-security-token"), token.clone()));
+    }
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request =
+        format!("{method}\n{uri}\n{query}\n{canonical_headers}\n{signed_headers}\n{EMPTY_SHA256}");
+
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        to_hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = hmac(
+        format!("AWS4{}", credentials.secret_key).as_bytes(),
+        date.as_bytes(),
+    );
+    let key = hmac(&key, region.as_bytes());
+    let key = hmac(&key, b"s3");
+    let key = hmac(&key, b"aws4_request");
+    let signature = to_hex(&hmac(&key, string_to_sign.as_bytes()));
+
+    headers.push((
+        String::from("authorization"),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key
+        ),
+    ));
+    // ureq derives the Host header from the URL itself.
+    headers.retain(|(name, _)| name != "host");
+    headers
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// URI-encodes `input` as SigV4 requires, leaving `/` alone unless `encode_slash` is set.
+fn uri_encode(input: &str, encode_slash: bool) -> String {
+    input
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(b).to_string()
+            }
+            b'/' if !encode_slash => String::from("/"),
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn host_of(endpoint: &str) -> String {
+    let without_scheme = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest);
+    without_scheme
+        .split('/')
+        .next()
+        .unwrap_or(without_scheme)
+        .to_string()
+}
+
+/// Returns the contents of every `<tag>...</tag>` element in `xml`.
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        match after.find(&close) {
+            Some(end) => {
+                found.push(&after[..end]);
+                rest = &after[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+/// Returns the unescaped text of the first `<tag>` element in `xml`.
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    elements(xml, tag).first().map(|text| {
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    })
+}
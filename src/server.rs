@@ -0,0 +1,347 @@
+use crate::crawl::{ObjectRecord, object_records};
+use crate::export::open_logical_file;
+use crate::history::object_history;
+use crate::inventory::read_object_inventory;
+use crate::layout::Layout;
+use crate::logical::logical_files;
+use crate::object::{get_object_spec_version, is_object_root};
+use crate::storage::is_storage_root;
+use serde_json::{Value, json};
+use std::{
+    io::{Error, ErrorKind},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+
+/// Page size used when `/objects` is requested without a `limit`.
+const DEFAULT_LIMIT: usize = 100;
+/// Largest page size `/objects` will return.
+const MAX_LIMIT: usize = 1000;
+
+/// Read-only HTTP API over a storage root.
+///
+/// Routes, all answering `GET` with JSON unless noted:
+///
+/// - `/objects?offset=N&limit=N`: objects ordered by path, with the number of crawl `errors`
+/// - `/objects/{id}`: object summary
+/// - `/objects/{id}/versions`: version history, oldest first
+/// - `/objects/{id}/files?version=vN`: logical files of a version (default: head)
+/// - `/objects/{id}/content?path=P&version=vN`: raw content of a logical file
+///
+/// `{id}` is the percent-encoded object identifier. Objects are located through the storage
+/// root's layout when it declares one, and by looking them up in the crawl otherwise.
+///
+/// The storage root is crawled once, when the server is bound; objects added afterwards are
+/// listed (and, without a layout, found) only after a restart. Objects the crawl could not read
+/// are left out of the listing and reported by `crawl_errors`.
+pub struct Server {
+    root: PathBuf,
+    layout: Option<Layout>,
+    /// Objects found by the crawl at startup, ordered by path.
+    records: Vec<ObjectRecord>,
+    /// Problems the crawl at startup ran into.
+    errors: Vec<Error>,
+    http: tiny_http::Server,
+}
+
+/// An API failure, answered with its status code and a JSON `error` message.
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e.kind() {
+            ErrorKind::NotFound => 404,
+            ErrorKind::InvalidInput => 400,
+            _ => 500,
+        };
+        Self {
+            status,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl Server {
+    /// Binds the API for the storage root at `root` to `addr` (e.g. "127.0.0.1:8080").
+    pub fn bind<P: AsRef<Path>>(root: P, addr: &str) -> Result<Self, Error> {
+        let root = root.as_ref().canonicalize()?;
+        if !is_storage_root(&root) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Not an OCFL storage root",
+            ));
+        }
+
+        let (mut records, errors) = object_records(&root);
+        records.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            layout: Layout::from_storage_root(&root)?,
+            records,
+            errors,
+            http: tiny_http::Server::http(addr).map_err(Error::other)?,
+            root,
+        })
+    }
+
+    /// Address the server is listening on.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Errors from the crawl at startup, such as objects whose inventory could not be read.
+    pub fn crawl_errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    /// Answers requests one at a time until the listener fails.
+    pub fn run(&self) -> Result<(), Error> {
+        loop {
+            let request = self.http.recv()?;
+            // A client hanging up mid-response must not stop the server.
+            let _ = self.respond(request);
+        }
+    }
+
+    fn respond(&self, request: Request) -> Result<(), Error> {
+        if *request.method() != Method::Get {
+            return request.respond(error_response(ApiError {
+                status: 405,
+                message: String::from("only GET is supported"),
+            }));
+        }
+
+        let (path, query) = match request.url().split_once('?') {
+            Some((path, query)) => (path.to_string(), parse_query(query)),
+            None => (request.url().to_string(), Vec::new()),
+        };
+        let param = |name: &str| {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let result = match segments.as_slice() {
+            ["objects"] => self
+                .list_objects(param("offset"), param("limit"))
+                .map(json_response),
+            ["objects", id] => self.object(&percent_decode(id)).map(json_response),
+            ["objects", id, "versions"] => self.versions(&percent_decode(id)).map(json_response),
+            ["objects", id, "files"] => self
+                .files(&percent_decode(id), param("version").as_deref())
+                .map(json_response),
+            ["objects", id, "content"] => match param("path") {
+                Some(logical_path) => self
+                    .locate(&percent_decode(id))
+                    .and_then(|object| {
+                        Ok(open_logical_file(
+                            object,
+                            param("version").as_deref(),
+                            &logical_path,
+                        )?)
+                    })
+                    .map(|file| {
+                        Response::from_file(file)
+                            .with_header(header("Content-Type", "application/octet-stream"))
+                            .boxed()
+                    }),
+                None => Err(ApiError {
+                    status: 400,
+                    message: String::from("missing 'path' query parameter"),
+                }),
+            },
+            _ => Err(ApiError {
+                status: 404,
+                message: format!("no route for {path}"),
+            }),
+        };
+
+        request.respond(result.unwrap_or_else(error_response))
+    }
+
+    fn list_objects(
+        &self,
+        offset: Option<String>,
+        limit: Option<String>,
+    ) -> Result<Value, ApiError> {
+        let offset = parse_number("offset", offset, 0)?;
+        let limit = parse_number("limit", limit, DEFAULT_LIMIT)?.min(MAX_LIMIT);
+
+        let objects: Vec<Value> = self
+            .records
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|record| {
+                json!({
+                    "id": record.id,
+                    "path": self.relative(&record.path),
+                    "head": record.head,
+                })
+            })
+            .collect();
+
+        Ok(json!({
+            "total": self.records.len(),
+            "offset": offset,
+            "limit": limit,
+            "errors": self.errors.len(),
+            "objects": objects,
+        }))
+    }
+
+    fn object(&self, id: &str) -> Result<Value, ApiError> {
+        let object = self.locate(id)?;
+        let inventory = read_object_inventory(&object)?;
+        Ok(json!({
+            "id": inventory.id,
+            "path": self.relative(&object),
+            "spec_version": get_object_spec_version(&object)?,
+            "head": inventory.head,
+            "digest_algorithm": inventory.digest_algorithm,
+            "versions": inventory.version_names(),
+        }))
+    }
+
+    fn versions(&self, id: &str) -> Result<Value, ApiError> {
+        let object = self.locate(id)?;
+        let versions: Vec<Value> = object_history(&object)?
+            .iter()
+            .map(|summary| {
+                let user = summary
+                    .user
+                    .as_ref()
+                    .map(|u| json!({"name": u.name, "address": u.address}));
+                json!({
+                    "version": summary.version,
+                    "created": summary.created,
+                    "message": summary.message,
+                    "user": user,
+                    "added": summary.added,
+                    "removed": summary.removed,
+                    "modified": summary.modified,
+                    "renamed": summary.renamed,
+                })
+            })
+            .collect();
+        Ok(Value::Array(versions))
+    }
+
+    fn files(&self, id: &str, version: Option<&str>) -> Result<Value, ApiError> {
+        let object = self.locate(id)?;
+        let files: Vec<Value> = logical_files(&object, version)?
+            .iter()
+            .map(|file| {
+                json!({
+                    "logical_path": file.logical_path,
+                    "digest": file.digest,
+                    "content_path": file.content_path,
+                    "size": file.size,
+                })
+            })
+            .collect();
+        Ok(Value::Array(files))
+    }
+
+    /// Finds the object root holding the object with identifier `id`.
+    fn locate(&self, id: &str) -> Result<PathBuf, ApiError> {
+        let not_found = || ApiError {
+            status: 404,
+            message: format!("object {id} not found"),
+        };
+
+        match &self.layout {
+            Some(layout) => {
+                let object = self.root.join(layout.object_path(id)?);
+                let inventory = read_object_inventory(&object).map_err(|_| not_found())?;
+                if is_object_root(&object) && inventory.id == id {
+                    Ok(object)
+                } else {
+                    Err(not_found())
+                }
+            }
+            None => self
+                .records
+                .iter()
+                .find(|record| record.id == id)
+                .map(|record| record.path.clone())
+                .ok_or_else(not_found),
+        }
+    }
+
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+type BoxedResponse = Response<Box<dyn std::io::Read + Send>>;
+
+fn json_response(value: Value) -> BoxedResponse {
+    Response::from_string(value.to_string())
+        .with_header(header("Content-Type", "application/json"))
+        .boxed()
+}
+
+fn error_response(error: ApiError) -> BoxedResponse {
+    json_response(json!({ "error": error.message })).with_status_code(StatusCode(error.status))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("static header is valid")
+}
+
+fn parse_number(name: &str, value: Option<String>, default: usize) -> Result<usize, ApiError> {
+    match value {
+        None => Ok(default),
+        Some(value) => value.parse().map_err(|_| ApiError {
+            status: 400,
+            message: format!("'{name}' must be a non-negative integer"),
+        }),
+    }
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                percent_decode(&key.replace('+', " ")),
+                percent_decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes, leaving malformed escapes as they are.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = [bytes[i + 1], bytes[i + 2]];
+            let hex = std::str::from_utf8(&hex).expect("hex digits are ASCII");
+            decoded.push(u8::from_str_radix(hex, 16).expect("validated hex digits"));
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,125 @@
+use crate::crawl::crawl;
+use crate::inventory::read_object_inventory;
+use crate::object::get_object_spec_version;
+use crate::usage::usage_for;
+use chrono::DateTime;
+use std::{collections::BTreeMap, io::Error, path::Path};
+
+/// Aggregate statistics over the objects of one or more storage roots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub objects: usize,
+    pub spec_versions: BTreeMap<String, usize>,
+    pub digest_algorithms: BTreeMap<String, usize>,
+    /// Number of objects keyed by how many versions they have.
+    pub version_counts: BTreeMap<usize, usize>,
+    pub logical_bytes: u64,
+    pub physical_bytes: u64,
+    pub oldest_version: Option<String>,
+    pub newest_version: Option<String>,
+    /// Directories that could not be read and objects whose inventory could not be parsed.
+    pub errors: usize,
+}
+
+impl Summary {
+    /// Adds the OCFL object at `path` to the summary.
+    pub fn add_object<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let p = path.as_ref();
+        let spec_version = get_object_spec_version(p)?;
+        let inventory = read_object_inventory(p)?;
+        let usage = usage_for(p, &inventory);
+
+        self.objects += 1;
+        *self.spec_versions.entry(spec_version).or_default() += 1;
+        *self
+            .digest_algorithms
+            .entry(inventory.digest_algorithm.clone())
+            .or_default() += 1;
+        *self
+            .version_counts
+            .entry(inventory.versions.len())
+            .or_default() += 1;
+        self.logical_bytes += usage.logical_bytes;
+        self.physical_bytes += usage.bytes;
+
+        for version in inventory.versions.values() {
+            let created = &version.created;
+            if self
+                .oldest_version
+                .as_ref()
+                .is_none_or(|oldest| is_before(created, oldest))
+            {
+                self.oldest_version = Some(created.clone());
+            }
+            if self
+                .newest_version
+                .as_ref()
+                .is_none_or(|newest| is_before(newest, created))
+            {
+                self.newest_version = Some(created.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds the objects counted in `other`, e.g. the summary of another storage root.
+    pub fn merge(&mut self, other: Summary) {
+        self.objects += other.objects;
+        for (spec_version, count) in other.spec_versions {
+            *self.spec_versions.entry(spec_version).or_default() += count;
+        }
+        for (algorithm, count) in other.digest_algorithms {
+            *self.digest_algorithms.entry(algorithm).or_default() += count;
+        }
+        for (versions, count) in other.version_counts {
+            *self.version_counts.entry(versions).or_default() += count;
+        }
+        self.logical_bytes += other.logical_bytes;
+        self.physical_bytes += other.physical_bytes;
+        self.errors += other.errors;
+
+        if let Some(oldest) = other.oldest_version
+            && self
+                .oldest_version
+                .as_ref()
+                .is_none_or(|current| is_before(&oldest, current))
+        {
+            self.oldest_version = Some(oldest);
+        }
+        if let Some(newest) = other.newest_version
+            && self
+                .newest_version
+                .as_ref()
+                .is_none_or(|current| is_before(current, &newest))
+        {
+            self.newest_version = Some(newest);
+        }
+    }
+}
+
+/// Summarizes every object under the storage root at `path`.
+pub fn summarize<P: AsRef<Path>>(path: P) -> Summary {
+    let mut summary = Summary::default();
+    let result = crawl(path);
+    summary.errors += result.errors.len();
+
+    for object in &result.objects {
+        if summary.add_object(object).is_err() {
+            summary.errors += 1;
+        }
+    }
+
+    summary
+}
+
+/// Compares two RFC 3339 timestamps, falling back to a string comparison if either is malformed.
+fn is_before(a: &str, b: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(a),
+        DateTime::parse_from_rfc3339(b),
+    ) {
+        (Ok(a), Ok(b)) => a < b,
+        _ => a < b,
+    }
+}
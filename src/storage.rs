@@ -1,20 +1,29 @@
-use crate::utils::is_directory;
-use std::path::Path;
+use crate::backend::{LocalStorage, Storage, join_path};
+use crate::layout::Layout;
+use serde_json::json;
+use std::{
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
 
 /// Returns true if `path` is a directory and contains a single OCFL storage root marker file
 /// ("0=ocfl_1.0" xor "0=ocfl_1.1") whose contents are "ocfl_1.0\n" and "ocfl_1.1\n" respectively.
 pub fn is_storage_root<P: AsRef<Path>>(path: P) -> bool {
-    let p = path.as_ref();
+    is_storage_root_in(&LocalStorage::new(path), "")
+}
 
-    if !is_directory(p) {
+/// Like `is_storage_root`, for the directory at `path` within `storage`.
+pub fn is_storage_root_in(storage: &dyn Storage, path: &str) -> bool {
+    if !storage.is_dir(path) {
         return false;
     }
 
-    let marker_10 = p.join("0=ocfl_1.0");
-    let marker_11 = p.join("0=ocfl_1.1");
+    let marker_10 = join_path(path, "0=ocfl_1.0");
+    let marker_11 = join_path(path, "0=ocfl_1.1");
 
-    let exists_10 = marker_10.is_file();
-    let exists_11 = marker_11.is_file();
+    let exists_10 = storage.is_file(&marker_10);
+    let exists_11 = storage.is_file(&marker_11);
 
     // Require exactly one marker file to be present (exclusive or).
     if exists_10 == exists_11 {
@@ -22,14 +31,74 @@ pub fn is_storage_root<P: AsRef<Path>>(path: P) -> bool {
     }
 
     if exists_10 {
-        match std::fs::read_to_string(&marker_10) {
+        match storage.read_to_string(&marker_10) {
             Ok(contents) => contents == "ocfl_1.0\n",
             Err(_) => false,
         }
     } else {
-        match std::fs::read_to_string(&marker_11) {
+        match storage.read_to_string(&marker_11) {
             Ok(contents) => contents == "ocfl_1.1\n",
             Err(_) => false,
         }
     }
 }
+
+/// Initializes an OCFL storage root at `path` following OCFL `spec_version` ("1.0" or "1.1")
+/// and arranging objects with `layout`.
+///
+/// Writes the `0=ocfl_<spec>` marker, a reference to the specification text, `ocfl_layout.json`
+/// and the layout's `extensions/<name>/config.json`. `path` must not exist or be an empty
+/// directory.
+pub fn init_storage_root<P: AsRef<Path>>(
+    path: P,
+    spec_version: &str,
+    layout: &Layout,
+) -> Result<(), Error> {
+    let p = path.as_ref();
+
+    let spec_file = match spec_version {
+        "1.0" => "ocfl_1.0.txt",
+        "1.1" => "ocfl_1.1.md",
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unsupported OCFL version {spec_version}"),
+            ));
+        }
+    };
+    if p.exists() && (!p.is_dir() || fs::read_dir(p)?.next().is_some()) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} exists and is not an empty directory", p.display()),
+        ));
+    }
+
+    let extension_dir = p.join("extensions").join(layout.extension_name());
+    fs::create_dir_all(&extension_dir)?;
+    fs::write(
+        p.join(format!("0=ocfl_{spec_version}")),
+        format!("ocfl_{spec_version}\n"),
+    )?;
+    fs::write(
+        p.join(spec_file),
+        format!(
+            "This directory is an OCFL storage root conforming to the Oxford Common File Layout \
+             Specification {spec_version}: https://ocfl.io/{spec_version}/spec/\n"
+        ),
+    )?;
+    fs::write(
+        p.join("ocfl_layout.json"),
+        to_pretty_json(&json!({
+            "extension": layout.extension_name(),
+            "description": layout.description(),
+        })),
+    )?;
+    fs::write(
+        extension_dir.join("config.json"),
+        to_pretty_json(&layout.to_config()),
+    )
+}
+
+fn to_pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).expect("JSON values serialize")
+}
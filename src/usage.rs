@@ -0,0 +1,157 @@
+use crate::crawl::crawl;
+use crate::inventory::{Inventory, content_file_path, read_object_inventory};
+use crate::object::is_object_root;
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Storage used by a single version of an object.
+///
+/// `new_*` counts content stored in this version's directory; `carried_*` counts logical files
+/// whose content was stored by an earlier version and is reused through deduplication.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionUsage {
+    pub version: String,
+    pub new_files: usize,
+    pub new_bytes: u64,
+    pub carried_files: usize,
+    pub carried_bytes: u64,
+    pub logical_files: usize,
+    pub logical_bytes: u64,
+}
+
+/// Storage used by an object: content files in its manifest and the head version's logical size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectUsage {
+    pub path: PathBuf,
+    pub id: String,
+    pub files: usize,
+    pub bytes: u64,
+    pub logical_bytes: u64,
+    pub versions: Vec<VersionUsage>,
+    /// Content paths in the manifest whose file could not be read; they count as 0 bytes.
+    pub missing: Vec<String>,
+}
+
+/// Totals for all objects under a storage root.
+#[derive(Debug, Default)]
+pub struct RootUsage {
+    pub objects: Vec<ObjectUsage>,
+    pub files: usize,
+    pub bytes: u64,
+    pub errors: Vec<Error>,
+}
+
+/// Computes the storage used by the OCFL object at `path`.
+pub fn object_usage<P: AsRef<Path>>(path: P) -> Result<ObjectUsage, Error> {
+    let p = path.as_ref();
+
+    if !is_object_root(p) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory(p)?;
+    Ok(usage_for(p, &inventory))
+}
+
+/// Computes the storage used by every object under the storage root at `path`.
+///
+/// Objects whose inventory cannot be read are recorded in `RootUsage::errors`.
+pub fn storage_root_usage<P: AsRef<Path>>(path: P) -> RootUsage {
+    let result = crawl(path);
+    let mut usage = RootUsage {
+        errors: result.errors,
+        ..RootUsage::default()
+    };
+
+    for object in result.objects {
+        match object_usage(&object) {
+            Ok(object_usage) => {
+                usage.files += object_usage.files;
+                usage.bytes += object_usage.bytes;
+                usage.objects.push(object_usage);
+            }
+            Err(e) => usage
+                .errors
+                .push(Error::new(e.kind(), format!("{}: {e}", object.display()))),
+        }
+    }
+
+    usage
+}
+
+pub(crate) fn usage_for(path: &Path, inventory: &Inventory) -> ObjectUsage {
+    let mut missing = Vec::new();
+    let sizes: HashMap<&str, u64> = inventory
+        .manifest
+        .values()
+        .flatten()
+        .map(|content_path| {
+            let size = match content_file_path(path, content_path).and_then(std::fs::metadata) {
+                Ok(metadata) => metadata.len(),
+                Err(_) => {
+                    missing.push(content_path.clone());
+                    0
+                }
+            };
+            (content_path.as_str(), size)
+        })
+        .collect();
+    missing.sort();
+
+    let mut versions = Vec::new();
+    let mut earlier: Vec<String> = Vec::new();
+    for name in inventory.version_names() {
+        let prefix = format!("{name}/");
+        let mut version = VersionUsage {
+            version: name.to_string(),
+            ..VersionUsage::default()
+        };
+
+        for (content_path, size) in &sizes {
+            if content_path.starts_with(&prefix) {
+                version.new_files += 1;
+                version.new_bytes += size;
+            }
+        }
+
+        for (digest, logical_paths) in &inventory.versions[name].state {
+            let size = inventory
+                .content_path(digest)
+                .and_then(|content_path| sizes.get(content_path))
+                .copied()
+                .unwrap_or(0);
+            let files = logical_paths.len();
+            version.logical_files += files;
+            version.logical_bytes += size * files as u64;
+
+            let carried = inventory.content_paths(digest).iter().any(|content_path| {
+                earlier
+                    .iter()
+                    .any(|prefix| content_path.starts_with(prefix.as_str()))
+            });
+            if carried {
+                version.carried_files += files;
+                version.carried_bytes += size * files as u64;
+            }
+        }
+
+        earlier.push(prefix);
+        versions.push(version);
+    }
+
+    ObjectUsage {
+        path: path.to_path_buf(),
+        id: inventory.id.clone(),
+        files: sizes.len(),
+        bytes: sizes.values().sum(),
+        logical_bytes: versions.last().map(|v| v.logical_bytes).unwrap_or(0),
+        versions,
+        missing,
+    }
+}
@@ -0,0 +1,367 @@
+use crate::backend::{EntryKind, LocalStorage, Storage, join_path};
+use crate::inventory::{
+    DigestMap, Inventory, Version, is_zero_padded, read_object_inventory_in, version_number,
+};
+use crate::object::is_object_root_in;
+use std::{
+    collections::BTreeSet,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+/// Whether a validation issue makes the object invalid or is only advisory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single problem found while validating an object, tagged with its OCFL validation code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub(crate) fn error(code: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message,
+        }
+    }
+
+    pub(crate) fn warning(code: &'static str, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code,
+            message,
+        }
+    }
+
+    /// Returns true for issues with `Severity::Error`.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// Compares the version directories and every `vN/inventory.json` of the object at `path`
+/// against its root inventory.
+///
+/// Reports directories in the object root that are not versions (E001), gaps in the version
+/// sequence (E009), prior inventories whose manifest disagrees with the root manifest (E010),
+/// inconsistent zero-padding (E011, E012, W001), version directories missing from or unknown to
+/// the inventory (E046), missing version inventories (W010) and prior inventories whose history
+/// disagrees with the head (E037, E040, E064, E066).
+pub fn check_version_consistency<P: AsRef<Path>>(path: P) -> Result<Vec<ValidationIssue>, Error> {
+    check_version_consistency_in(&LocalStorage::new(path), "")
+}
+
+/// Like `check_version_consistency`, for the object root at `path` within `storage`.
+pub fn check_version_consistency_in(
+    storage: &dyn Storage,
+    path: &str,
+) -> Result<Vec<ValidationIssue>, Error> {
+    if !is_object_root_in(storage, path) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Not an OCFL object root",
+        ));
+    }
+
+    let inventory = read_object_inventory_in(storage, path)?;
+    let mut issues = Vec::new();
+
+    let names = inventory.version_names();
+    if names.is_empty() {
+        issues.push(ValidationIssue::error(
+            "E008",
+            "inventory does not contain any versions".to_string(),
+        ));
+        return Ok(issues);
+    }
+
+    check_sequence(&inventory, &names, &mut issues);
+
+    let mut dirs = Vec::new();
+    for entry in storage.read_dir(path)? {
+        if entry.kind != EntryKind::Dir || OBJECT_ROOT_DIRS.contains(&entry.name.as_str()) {
+            continue;
+        }
+        if version_number(&entry.name).is_some() {
+            dirs.push(entry.name);
+        } else {
+            issues.push(ValidationIssue::error(
+                "E001",
+                format!(
+                    "directory {} in the object root is not a version directory",
+                    entry.name
+                ),
+            ));
+        }
+    }
+    dirs.sort_by_key(|name| version_number(name));
+
+    check_padding(&names, &dirs, &mut issues);
+
+    for name in &names {
+        if !dirs.iter().any(|dir| dir == name) {
+            issues.push(ValidationIssue::error(
+                "E046",
+                format!("version {name} is listed in the inventory but has no version directory"),
+            ));
+        }
+    }
+    for dir in &dirs {
+        if !inventory.versions.contains_key(dir) {
+            issues.push(ValidationIssue::error(
+                "E046",
+                format!("version directory {dir} is not listed in the inventory"),
+            ));
+        }
+    }
+
+    for name in names
+        .iter()
+        .filter(|name| dirs.iter().any(|dir| dir == *name))
+    {
+        let version_inventory = join_path(&join_path(path, name), "inventory.json");
+        if !storage.is_file(&version_inventory) {
+            issues.push(ValidationIssue::warning(
+                "W010",
+                format!("version directory {name} does not contain an inventory.json"),
+            ));
+            continue;
+        }
+        match storage
+            .read_to_string(&version_inventory)
+            .and_then(|json| Inventory::from_json(&json))
+        {
+            Ok(prior) => compare_prior(&inventory, name, &prior, &mut issues),
+            Err(e) => issues.push(ValidationIssue::error(
+                "E033",
+                format!("{name}/inventory.json could not be parsed: {e}"),
+            )),
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Directories other than versions that may appear in an object root.
+const OBJECT_ROOT_DIRS: [&str; 2] = ["extensions", "logs"];
+
+fn check_sequence(inventory: &Inventory, names: &[&str], issues: &mut Vec<ValidationIssue>) {
+    let numbers: Vec<u32> = names
+        .iter()
+        .filter_map(|name| version_number(name))
+        .collect();
+    for (expected, name) in (1..).zip(names) {
+        if version_number(name) != Some(expected) {
+            issues.push(ValidationIssue::error(
+                "E009",
+                format!(
+                    "version sequence must run from 1 without gaps, found {}",
+                    names.join(", ")
+                ),
+            ));
+            break;
+        }
+    }
+
+    if numbers.len() != names.len() {
+        issues.push(ValidationIssue::error(
+            "E046",
+            "inventory contains version names that are not of the form vN".to_string(),
+        ));
+    }
+
+    if names.last() != Some(&inventory.head.as_str()) {
+        issues.push(ValidationIssue::error(
+            "E040",
+            format!(
+                "head is {} but the highest version is {}",
+                inventory.head,
+                names.last().unwrap_or(&"")
+            ),
+        ));
+    }
+}
+
+fn check_padding(names: &[&str], dirs: &[String], issues: &mut Vec<ValidationIssue>) {
+    let all: BTreeSet<&str> = names
+        .iter()
+        .copied()
+        .chain(dirs.iter().map(String::as_str))
+        .collect();
+
+    let padded_lengths: BTreeSet<usize> = all
+        .iter()
+        .filter(|name| is_zero_padded(name))
+        .map(|name| name.len())
+        .collect();
+
+    if padded_lengths.is_empty() {
+        return;
+    }
+
+    issues.push(ValidationIssue::warning(
+        "W001",
+        "version directory names should not be zero-padded".to_string(),
+    ));
+
+    if padded_lengths.len() > 1 {
+        let padded: Vec<&str> = all.iter().copied().filter(|n| is_zero_padded(n)).collect();
+        issues.push(ValidationIssue::error(
+            "E011",
+            format!(
+                "zero-padded version names must all have the same length, found {}",
+                padded.join(", ")
+            ),
+        ));
+    }
+
+    let mixed: Vec<&str> = all
+        .iter()
+        .copied()
+        .filter(|name| !is_zero_padded(name) && !padded_lengths.contains(&name.len()))
+        .collect();
+    if !mixed.is_empty() {
+        issues.push(ValidationIssue::error(
+            "E012",
+            format!(
+                "version names mix zero-padded and unpadded numbering: {}",
+                mixed.join(", ")
+            ),
+        ));
+    }
+}
+
+fn compare_prior(
+    head: &Inventory,
+    name: &str,
+    prior: &Inventory,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    if prior.id != head.id {
+        issues.push(ValidationIssue::error(
+            "E037",
+            format!(
+                "{name}/inventory.json has id {} but the root inventory has id {}",
+                prior.id, head.id
+            ),
+        ));
+    }
+
+    if prior.head != name {
+        issues.push(ValidationIssue::error(
+            "E040",
+            format!("{name}/inventory.json has head {}", prior.head),
+        ));
+    }
+
+    if name == head.head && prior != head {
+        issues.push(ValidationIssue::error(
+            "E064",
+            format!("{name}/inventory.json differs from the root inventory"),
+        ));
+    } else if name != head.head {
+        compare_manifest(head, name, prior, issues);
+    }
+
+    for (version, block) in &prior.versions {
+        match head.versions.get(version) {
+            None => issues.push(ValidationIssue::error(
+                "E066",
+                format!(
+                    "{name}/inventory.json records version {version} \
+                     which the root inventory does not"
+                ),
+            )),
+            Some(current) => {
+                let same_digests = prior.digest_algorithm == head.digest_algorithm;
+                for field in differing_fields(block, current, same_digests) {
+                    issues.push(ValidationIssue::error(
+                        "E066",
+                        format!(
+                            "{name}/inventory.json disagrees with the root inventory \
+                             on the {field} of version {version}"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Content written before version `name` must be listed under the same digest in the root
+/// manifest; digests are only compared when both inventories use the same algorithm.
+fn compare_manifest(
+    head: &Inventory,
+    name: &str,
+    prior: &Inventory,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let same_digests = prior.digest_algorithm == head.digest_algorithm;
+    let current = normalized(&head.manifest);
+    for (digest, path) in normalized(&prior.manifest) {
+        let listed = if same_digests {
+            current.contains(&(digest, path))
+        } else {
+            current.iter().any(|(_, current)| *current == path)
+        };
+        if !listed {
+            issues.push(ValidationIssue::error(
+                "E010",
+                format!(
+                    "the manifest entry for {path} in {name}/inventory.json \
+                     is missing from the root manifest"
+                ),
+            ));
+        }
+    }
+}
+
+fn differing_fields(prior: &Version, current: &Version, same_digests: bool) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+
+    if prior.created != current.created {
+        fields.push("created timestamp");
+    }
+    if prior.message != current.message {
+        fields.push("message");
+    }
+    if prior.user != current.user {
+        fields.push("user");
+    }
+
+    // Digests are only comparable when both inventories use the same algorithm;
+    // otherwise fall back to comparing the set of logical paths.
+    let state_differs = if same_digests {
+        normalized(&prior.state) != normalized(&current.state)
+    } else {
+        logical_paths(&prior.state) != logical_paths(&current.state)
+    };
+    if state_differs {
+        fields.push("state");
+    }
+
+    fields
+}
+
+fn normalized(state: &DigestMap) -> BTreeSet<(String, &str)> {
+    state
+        .iter()
+        .flat_map(|(digest, paths)| {
+            paths
+                .iter()
+                .map(move |path| (digest.to_lowercase(), path.as_str()))
+        })
+        .collect()
+}
+
+fn logical_paths(state: &DigestMap) -> BTreeSet<&str> {
+    state.values().flatten().map(String::as_str).collect()
+}
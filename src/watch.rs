@@ -0,0 +1,200 @@
+use crate::digest::file_digest;
+use crate::index::{ChangeEvent, ChangeKind};
+use crate::inventory::{read_object_inventory, read_sidecar_digest};
+use crate::object::is_object_root;
+use crate::storage::is_storage_root;
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+/// Watches a storage root with inotify and reports objects as they are created or updated.
+///
+/// An object is only reported once its root `inventory.json` and inventory sidecar have both
+/// been written and agree with each other, so objects still being copied in are not reported
+/// half-finished. Objects already present when the watcher starts are not reported.
+pub struct Watcher {
+    inotify: Inotify,
+    buffer: Vec<u8>,
+    dirs: HashMap<WatchDescriptor, PathBuf>,
+    heads: HashMap<PathBuf, String>,
+}
+
+/// A raw inotify event with its watch resolved to a directory.
+struct RawEvent {
+    wd: WatchDescriptor,
+    dir: PathBuf,
+    name: Option<OsString>,
+    mask: EventMask,
+}
+
+impl Watcher {
+    /// Starts watching the storage root at `path` and every directory below it.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let root = path.as_ref().canonicalize()?;
+        if !is_storage_root(&root) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Not an OCFL storage root",
+            ));
+        }
+
+        let mut watcher = Self {
+            inotify: Inotify::init()?,
+            buffer: vec![0; 64 * 1024],
+            dirs: HashMap::new(),
+            heads: HashMap::new(),
+        };
+        watcher.add_tree(&root, false)?;
+        Ok(watcher)
+    }
+
+    /// Blocks until at least one filesystem event arrives and returns the resulting changes,
+    /// which may be empty if the events did not complete an object.
+    pub fn wait(&mut self) -> Result<Vec<ChangeEvent>, Error> {
+        let raw = {
+            let events = self.inotify.read_events_blocking(&mut self.buffer)?;
+            resolve(&self.dirs, events)
+        };
+        self.handle(raw)
+    }
+
+    /// Returns the changes from any filesystem events that are already queued, without blocking.
+    pub fn poll(&mut self) -> Result<Vec<ChangeEvent>, Error> {
+        let raw = match self.inotify.read_events(&mut self.buffer) {
+            Ok(events) => resolve(&self.dirs, events),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        self.handle(raw)
+    }
+
+    fn handle(&mut self, raw: Vec<RawEvent>) -> Result<Vec<ChangeEvent>, Error> {
+        let mut changes = Vec::new();
+
+        for event in raw {
+            if event.mask.contains(EventMask::IGNORED) {
+                self.dirs.remove(&event.wd);
+                continue;
+            }
+            let Some(name) = event.name else {
+                continue;
+            };
+            let path = event.dir.join(&name);
+
+            if event.mask.contains(EventMask::ISDIR) {
+                if event
+                    .mask
+                    .intersects(EventMask::CREATE | EventMask::MOVED_TO)
+                {
+                    // Directories moved in whole never produce events for their contents.
+                    match self.add_tree(&path, true) {
+                        Ok(found) => changes.extend(found),
+                        // Already gone again, e.g. a temporary directory.
+                        Err(e) if e.kind() == ErrorKind::NotFound => {}
+                        Err(e) => return Err(e),
+                    }
+                } else if event
+                    .mask
+                    .intersects(EventMask::DELETE | EventMask::MOVED_FROM)
+                {
+                    self.heads.retain(|object, _| !object.starts_with(&path));
+                }
+            } else if event
+                .mask
+                .intersects(EventMask::CLOSE_WRITE | EventMask::MOVED_TO)
+            {
+                let name = name.to_string_lossy();
+                if name == "inventory.json" || name.starts_with("inventory.json.") {
+                    changes.extend(self.check_object(&event.dir));
+                }
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Watches `dir` and the directories below it, stopping at object roots. Object roots found
+    /// along the way are checked when `report` is set and otherwise only remembered.
+    fn add_tree(&mut self, dir: &Path, report: bool) -> Result<Vec<ChangeEvent>, Error> {
+        let mut changes = Vec::new();
+
+        let wd = self.inotify.watches().add(
+            dir,
+            WatchMask::CREATE
+                | WatchMask::CLOSE_WRITE
+                | WatchMask::MOVED_TO
+                | WatchMask::MOVED_FROM
+                | WatchMask::DELETE,
+        )?;
+        self.dirs.insert(wd, dir.to_path_buf());
+
+        if is_object_root(dir) {
+            if report {
+                changes.extend(self.check_object(dir));
+            } else if let Ok(inventory) = read_object_inventory(dir) {
+                self.heads.insert(dir.to_path_buf(), inventory.head);
+            }
+            return Ok(changes);
+        }
+
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                changes.extend(self.add_tree(&entry.path(), report)?);
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Reports the object at `dir` if it is complete and its head differs from the last one seen.
+    fn check_object(&mut self, dir: &Path) -> Option<ChangeEvent> {
+        if !is_object_root(dir) {
+            return None;
+        }
+
+        // A partially written inventory fails to parse or disagrees with its sidecar; wait for
+        // the write that completes the pair.
+        let inventory = read_object_inventory(dir).ok()?;
+        let algorithm = inventory.algorithm().ok()?;
+        let expected = read_sidecar_digest(dir, algorithm).ok()?;
+        let actual = file_digest(algorithm, dir.join("inventory.json")).ok()?;
+        if !expected.eq_ignore_ascii_case(&actual) {
+            return None;
+        }
+
+        let kind = match self.heads.get(dir) {
+            Some(head) if *head == inventory.head => return None,
+            Some(_) => ChangeKind::Updated,
+            None => ChangeKind::Created,
+        };
+        self.heads.insert(dir.to_path_buf(), inventory.head.clone());
+
+        Some(ChangeEvent {
+            kind,
+            path: dir.to_path_buf(),
+            id: inventory.id,
+            head: inventory.head,
+        })
+    }
+}
+
+fn resolve<'a>(
+    dirs: &HashMap<WatchDescriptor, PathBuf>,
+    events: impl Iterator<Item = inotify::Event<&'a std::ffi::OsStr>>,
+) -> Vec<RawEvent> {
+    events
+        .filter_map(|event| {
+            Some(RawEvent {
+                wd: event.wd.clone(),
+                dir: dirs.get(&event.wd)?.clone(),
+                name: event.name.map(OsString::from),
+                mask: event.mask,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,153 @@
+use serde_json::Value;
+use std::{
+    collections::VecDeque,
+    io::{Error, ErrorKind},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Batches JSON records and delivers them to an HTTP endpoint as JSON arrays.
+///
+/// Each batch is sent as one POST request. Connection failures and `429` or `5xx` responses are
+/// retried with exponential backoff; any other error response fails the delivery immediately.
+///
+/// After a failed delivery the webhook waits out one more backoff period before sending again:
+/// until then `push` only queues and `flush` fails with `ErrorKind::WouldBlock`. At most
+/// `max_pending` records are queued; older ones are dropped first.
+#[derive(Debug)]
+pub struct Webhook {
+    pub url: String,
+    /// Extra request headers as (name, value) pairs.
+    pub headers: Vec<(String, String)>,
+    /// Number of records sent per request.
+    pub batch_size: usize,
+    /// Number of times a failed request is retried before giving up.
+    pub retries: u32,
+    /// Delay before the first retry, doubled for every following attempt.
+    pub backoff: Duration,
+    /// Number of records kept queued while the endpoint is unreachable.
+    pub max_pending: usize,
+    pending: VecDeque<Value>,
+    dropped: usize,
+    retry_at: Option<Instant>,
+    agent: ureq::Agent,
+}
+
+impl Webhook {
+    /// Creates a webhook posting to `url` with a batch size of 100, three retries and room for
+    /// 10000 queued records.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            headers: Vec::new(),
+            batch_size: 100,
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            max_pending: 10_000,
+            pending: VecDeque::new(),
+            dropped: 0,
+            retry_at: None,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// Queues `record`, sending the pending batch once it reaches `batch_size` records unless a
+    /// recent delivery failed.
+    pub fn push(&mut self, record: Value) -> Result<(), Error> {
+        self.pending.push_back(record);
+        while self.pending.len() > self.max_pending.max(1) {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        if self.pending.len() >= self.batch_size.max(1) && !self.is_paused() {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Sends any queued records. Records stay queued if delivery fails.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        if let Some(retry_at) = self.retry_at.filter(|_| self.is_paused()) {
+            return Err(Error::new(
+                ErrorKind::WouldBlock,
+                format!(
+                    "delivery to {} paused for {:.1}s after a failure",
+                    self.url,
+                    retry_at
+                        .saturating_duration_since(Instant::now())
+                        .as_secs_f64()
+                ),
+            ));
+        }
+
+        let body = Value::Array(self.pending.iter().cloned().collect());
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.send(&body) {
+                Ok(()) => {
+                    self.pending.clear();
+                    self.retry_at = None;
+                    return Ok(());
+                }
+                Err((e, retryable)) => {
+                    if !retryable || attempt >= self.retries {
+                        self.retry_at = Some(Instant::now() + delay);
+                        return Err(e);
+                    }
+                }
+            }
+            thread::sleep(delay);
+            delay *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Number of records dropped so far because the queue was full.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    fn is_paused(&self) -> bool {
+        self.retry_at
+            .is_some_and(|retry_at| Instant::now() < retry_at)
+    }
+
+    /// Sends one request; errors are paired with whether they are worth retrying.
+    fn send(&self, body: &Value) -> Result<(), (Error, bool)> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json");
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+
+        match request.send_string(&body.to_string()) {
+            Ok(_) => Ok(()),
+            Err(ureq::Error::Status(status, _)) => Err((
+                Error::other(format!("{} responded with status {status}", self.url)),
+                status == 429 || status >= 500,
+            )),
+            Err(ureq::Error::Transport(e)) => Err((Error::other(e.to_string()), true)),
+        }
+    }
+}
+
+/// Parses a header given as `"Name: value"`.
+pub fn parse_header(header: &str) -> Result<(String, String), Error> {
+    match header.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("header must be of the form 'Name: value', got '{header}'"),
+        )),
+    }
+}
@@ -0,0 +1,77 @@
+use ocfl_crawler_rust::analyze_dedup;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+#[test]
+fn finds_content_shared_across_storage_roots() {
+    let report = analyze_dedup(&["tests/cli/1.0", "tests/cli/1.1"]);
+
+    assert!(report.errors.is_empty());
+    assert_eq!(report.objects, 4);
+    assert_eq!(report.shared.len(), 1);
+    assert_eq!(report.shared[0].objects.len(), 2);
+    assert_eq!(report.total_bytes, 40);
+    assert_eq!(report.unique_bytes, 20);
+    assert_eq!(report.duplicated_bytes(), 20);
+}
+
+#[test]
+fn matches_content_across_digest_algorithms() {
+    // spec-ex-sha256 uses sha256 for its manifest but records sha512 fixity digests,
+    // which link its v1 content with that of spec-ex-full.
+    let report = analyze_dedup(&["tests/ocfl/1.1/good-objects"]);
+
+    assert_eq!(report.objects, 2);
+    assert_eq!(report.shared.len(), 3);
+    assert!(report.shared.iter().all(|s| s.algorithm == "sha512"));
+    assert_eq!(report.duplicated_bytes(), 33);
+}
+
+#[test]
+fn sizes_shared_content_from_a_copy_that_exists() {
+    let dir = unique_dir("dedup_missing");
+    copy_dir(Path::new("tests/cli/1.1"), &dir);
+    fs::remove_file(dir.join("minimal_one_version_one_file/v1/content/a_file.txt")).unwrap();
+
+    // The first replica found is the one whose content file is missing.
+    let report = analyze_dedup(&[dir.as_path(), Path::new("tests/cli/1.1")]);
+
+    assert_eq!(report.shared.len(), 1);
+    assert_eq!(report.shared[0].size, 20);
+    assert_eq!(report.unique_bytes, 20);
+
+    fs::remove_dir_all(&dir).ok();
+}
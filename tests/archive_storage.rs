@@ -0,0 +1,154 @@
+use flate2::{Compression, write::GzEncoder};
+use ocfl_crawler_rust::{
+    archive::{ArchiveFormat, ArchiveStorage},
+    backend::{EntryKind, Storage, is_archive, open_storage},
+    check_version_consistency,
+    crawl::crawl_in,
+    object::{get_object_id_in, is_object_root_in},
+    storage::is_storage_root_in,
+    validate::check_version_consistency_in,
+};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use walkdir::WalkDir;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+const ROOT: &str = "tests/cli/1.1";
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+/// Packs the storage root under a top-level `storage/` directory, like `tar -cf x.tar storage`.
+fn write_tar<W: Write>(writer: W) -> W {
+    let mut builder = tar::Builder::new(writer);
+    builder.append_dir_all("storage", ROOT).unwrap();
+    builder.into_inner().unwrap()
+}
+
+fn write_zip(path: &Path, compression: CompressionMethod) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    let options = SimpleFileOptions::default().compression_method(compression);
+    for entry in WalkDir::new(ROOT).into_iter().filter_map(Result::ok) {
+        let relative = entry.path().strip_prefix(ROOT).unwrap().to_string_lossy();
+        let name = format!("storage/{relative}");
+        if entry.file_type().is_dir() {
+            zip.add_directory(name, options).unwrap();
+        } else {
+            zip.start_file(name, options).unwrap();
+            zip.write_all(&fs::read(entry.path()).unwrap()).unwrap();
+        }
+    }
+    zip.finish().unwrap();
+}
+
+fn assert_storage_root(storage: &dyn Storage) {
+    assert!(is_storage_root_in(storage, ""));
+    assert!(storage.is_dir("minimal_no_content"));
+    assert!(!storage.is_dir("missing"));
+
+    let result = crawl_in(storage, "");
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(
+        result.objects,
+        vec![
+            PathBuf::from("minimal_no_content"),
+            PathBuf::from("minimal_one_version_one_file"),
+        ]
+    );
+
+    let object = "minimal_one_version_one_file";
+    assert!(is_object_root_in(storage, object));
+    assert_eq!(get_object_id_in(storage, object).unwrap(), "ark:123/abc");
+
+    let file = format!("{object}/v1/content/a_file.txt");
+    let local = Path::new(ROOT).join(&file);
+    let metadata = storage.metadata(&file).unwrap();
+    assert_eq!(metadata.kind, EntryKind::File);
+    assert_eq!(metadata.size, fs::metadata(&local).unwrap().len());
+    assert_eq!(storage.read(&file).unwrap(), fs::read(&local).unwrap());
+
+    assert_eq!(
+        check_version_consistency_in(storage, object).unwrap(),
+        check_version_consistency(Path::new(ROOT).join(object)).unwrap()
+    );
+}
+
+#[test]
+fn reads_tar_tar_gz_and_zip_archives() {
+    let dir = unique_dir("archive_storage");
+
+    let tar = dir.join("root.tar");
+    write_tar(File::create(&tar).unwrap());
+
+    let tar_gz = dir.join("root.tar.gz");
+    write_tar(GzEncoder::new(
+        File::create(&tar_gz).unwrap(),
+        Compression::default(),
+    ))
+    .finish()
+    .unwrap();
+
+    let zip = dir.join("root.zip");
+    write_zip(&zip, CompressionMethod::Deflated);
+    let stored_zip = dir.join("stored.zip");
+    write_zip(&stored_zip, CompressionMethod::Stored);
+
+    for (path, format) in [
+        (&tar, ArchiveFormat::Tar),
+        (&tar_gz, ArchiveFormat::TarGz),
+        (&zip, ArchiveFormat::Zip),
+        (&stored_zip, ArchiveFormat::Zip),
+    ] {
+        let storage = ArchiveStorage::open(path).unwrap();
+        assert_eq!(storage.format(), format);
+        assert_storage_root(&storage);
+    }
+
+    // A path inside the archive opens that directory.
+    let object = tar.join("minimal_one_version_one_file");
+    let location = object.to_str().unwrap();
+    assert!(is_archive(location));
+    let storage = open_storage(location).unwrap();
+    assert!(is_object_root_in(&*storage, ""));
+    assert!(open_storage(tar.join("missing").to_str().unwrap()).is_err());
+
+    // A directory named like an archive is a directory.
+    let named_like_archive = dir.join("directory.tar");
+    fs::create_dir_all(&named_like_archive).unwrap();
+    assert!(!is_archive(named_like_archive.to_str().unwrap()));
+    assert!(is_archive(zip.to_str().unwrap()));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn detects_archive_locations() {
+    assert_eq!(
+        ArchiveFormat::from_path("a.TGZ"),
+        Some(ArchiveFormat::TarGz)
+    );
+    assert!(!is_archive("missing.tar"));
+    assert!(!is_archive("tests/cli/1.1"));
+    assert!(!is_archive("s3://bucket/root.tar"));
+    assert!(ArchiveStorage::open("tests/cli/1.1").is_err());
+}
@@ -0,0 +1,67 @@
+use ocfl_crawler_rust::check_version_consistency;
+
+fn codes(path: &str) -> Vec<&'static str> {
+    check_version_consistency(path)
+        .expect("fixture should be an OCFL object root")
+        .iter()
+        .map(|issue| issue.code)
+        .collect()
+}
+
+#[test]
+fn consistent_object_has_no_issues() {
+    assert!(codes("tests/ocfl/1.1/good-objects/spec-ex-full").is_empty());
+}
+
+#[test]
+fn zero_padded_versions_warn() {
+    assert_eq!(
+        codes("tests/ocfl/1.1/warn-objects/W001_zero_padded_versions"),
+        vec!["W001"]
+    );
+}
+
+#[test]
+fn inconsistent_padding_is_an_error() {
+    let found = codes("tests/ocfl/1.1/bad-objects/E011_inconsistent_padding");
+    assert!(found.contains(&"E011"), "expected E011, got {found:?}");
+}
+
+#[test]
+fn missing_and_extra_version_directories_are_errors() {
+    assert_eq!(
+        codes("tests/ocfl/1.1/bad-objects/E046_missing_version_dir"),
+        vec!["E046"]
+    );
+    assert_eq!(
+        codes("tests/ocfl/1.1/bad-objects/E046_extra_version_dir"),
+        vec!["E046"]
+    );
+}
+
+#[test]
+fn unexpected_root_directories_are_errors() {
+    assert_eq!(
+        codes("tests/ocfl/1.1/bad-objects/E001_extra_dir_in_root"),
+        vec!["E001"]
+    );
+}
+
+#[test]
+fn prior_manifest_disagreeing_with_the_root_is_an_error() {
+    assert_eq!(
+        codes("tests/ocfl/1.1/bad-objects/E010_version_manifest_mismatch"),
+        vec!["E010"]
+    );
+}
+
+#[test]
+fn rewritten_history_is_an_error() {
+    let found = codes("tests/ocfl/1.1/bad-objects/E066_rewritten_history");
+    assert!(found.contains(&"E066"), "expected E066, got {found:?}");
+}
+
+#[test]
+fn not_an_object_root_is_rejected() {
+    assert!(check_version_consistency("tests/ocfl").is_err());
+}
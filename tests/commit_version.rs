@@ -0,0 +1,164 @@
+use ocfl_crawler_rust::{
+    check_fixity, check_version_consistency, commit_version,
+    create::VersionInfo,
+    create_object,
+    digest::DigestAlgorithm,
+    inventory::{User, read_object_inventory, read_sidecar_digest},
+    logical_files,
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+/// Creates an object holding `a.txt` and `b.txt` in a flat-direct storage root.
+fn setup(dir: &Path) -> PathBuf {
+    let root = dir.join("root");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    fs::write(
+        root.join("ocfl_layout.json"),
+        r#"{"extension": "0002-flat-direct-storage-layout", "description": "test"}"#,
+    )
+    .unwrap();
+
+    let source = dir.join("v1");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    fs::write(source.join("b.txt"), "beta").unwrap();
+    create_object(
+        &root,
+        "obj",
+        &source,
+        DigestAlgorithm::Sha512,
+        &VersionInfo::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn commits_a_new_version() {
+    let dir = unique_dir("commit_version");
+    let object = setup(&dir);
+
+    // Keep a.txt, rename b.txt, add a new file.
+    let source = dir.join("v2");
+    fs::create_dir_all(source.join("sub")).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    fs::write(source.join("sub/b.txt"), "beta").unwrap();
+    fs::write(source.join("c.txt"), "gamma").unwrap();
+
+    let info = VersionInfo {
+        message: Some(String::from("second")),
+        user: Some(User {
+            name: String::from("Bob"),
+            address: None,
+        }),
+        created: None,
+    };
+    let commit = commit_version(&object, &source, &info).unwrap();
+    assert_eq!(commit.version, "v2");
+    let added: Vec<&str> = commit.diff.added.iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(added, vec!["c.txt"]);
+    assert_eq!(commit.diff.renamed.len(), 1);
+    assert_eq!(commit.diff.renamed[0].to, "sub/b.txt");
+    assert!(commit.diff.removed.is_empty() && commit.diff.modified.is_empty());
+
+    // Only the new content is stored in v2.
+    assert!(object.join("v2/content/c.txt").is_file());
+    assert!(!object.join("v2/content/a.txt").exists());
+    assert!(!object.join("v2/content/sub").exists());
+
+    let inventory = read_object_inventory(&object).unwrap();
+    assert_eq!(inventory.head, "v2");
+    assert_eq!(inventory.manifest.len(), 3);
+    assert_eq!(inventory.versions["v2"].message.as_deref(), Some("second"));
+    assert_eq!(read_object_inventory(object.join("v2")).unwrap(), inventory);
+    assert_eq!(
+        read_sidecar_digest(&object, DigestAlgorithm::Sha512).unwrap(),
+        read_sidecar_digest(object.join("v2"), DigestAlgorithm::Sha512).unwrap()
+    );
+
+    let paths: Vec<String> = logical_files(&object, None)
+        .unwrap()
+        .into_iter()
+        .map(|f| f.logical_path)
+        .collect();
+    assert_eq!(paths, vec!["a.txt", "c.txt", "sub/b.txt"]);
+    assert!(check_version_consistency(&object).unwrap().is_empty());
+    assert!(check_fixity(&object).unwrap().is_empty());
+
+    // Nothing but the object's own files is left behind.
+    let mut names: Vec<String> = fs::read_dir(&object)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "0=ocfl_object_1.1",
+            "inventory.json",
+            "inventory.json.sha512",
+            "v1",
+            "v2"
+        ]
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn refuses_unchanged_state() {
+    let dir = unique_dir("commit_version_unchanged");
+    let object = setup(&dir);
+
+    let err = commit_version(&object, dir.join("v1"), &VersionInfo::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(!object.join("v2").exists());
+    assert_eq!(read_object_inventory(&object).unwrap().head, "v1");
+    assert_eq!(fs::read_dir(&object).unwrap().count(), 4);
+
+    let err = commit_version(dir.join("v1"), dir.join("v1"), &VersionInfo::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn refuses_to_run_next_to_a_staging_directory() {
+    let dir = unique_dir("commit_version_staging");
+    let object = setup(&dir);
+    fs::create_dir(object.join(".v2.staging-1")).unwrap();
+    let source = dir.join("v2");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("c.txt"), "gamma").unwrap();
+
+    let err = commit_version(&object, &source, &VersionInfo::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert!(!object.join("v2").exists());
+    assert_eq!(read_object_inventory(&object).unwrap().head, "v1");
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,130 @@
+use ocfl_crawler_rust::{
+    check_fixity,
+    compare::{compare_content, compare_fixity},
+    compare_roots,
+    digest::{DigestAlgorithm, hex_digest},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+#[test]
+fn matches_objects_by_identifier() {
+    let comparison = compare_roots("tests/cli/1.0", "tests/cli/1.1");
+
+    assert!(comparison.errors.is_empty());
+    assert!(comparison.missing_in_a.is_empty());
+    assert!(comparison.missing_in_b.is_empty());
+    assert_eq!(comparison.pairs.len(), 2);
+    assert!(comparison.pairs.iter().all(|p| p.heads_match()));
+    // The 1.0 and 1.1 copies have different inventory "type" values.
+    assert!(!comparison.is_consistent());
+}
+
+#[test]
+fn reports_objects_missing_on_either_side() {
+    let comparison = compare_roots("tests/cli/1.0", "tests/ocfl/1.1/good-objects");
+
+    assert!(comparison.pairs.is_empty());
+    assert_eq!(comparison.missing_in_a.len(), 2);
+    assert_eq!(comparison.missing_in_b.len(), 2);
+}
+
+#[test]
+fn check_fixity_reports_tampered_content() {
+    assert!(
+        check_fixity("tests/ocfl/1.1/good-objects/spec-ex-full")
+            .unwrap()
+            .is_empty()
+    );
+
+    let issues = check_fixity("tests/ocfl/1.1/bad-objects/E092_content_digest_mismatch").unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].path, "v1/content/image.tiff");
+    assert!(issues[0].actual.is_some());
+}
+
+#[test]
+fn compare_fixity_reports_each_side_separately() {
+    let dir = unique_dir("compare_fixity");
+    copy_dir(Path::new("tests/cli/1.1"), &dir.join("a"));
+    copy_dir(Path::new("tests/cli/1.1"), &dir.join("b"));
+    fs::remove_file(dir.join("b/minimal_one_version_one_file/inventory.json.sha512")).unwrap();
+
+    let comparison = compare_roots(dir.join("a"), dir.join("b"));
+    let pair = comparison
+        .pairs
+        .iter()
+        .find(|pair| pair.a.path.ends_with("minimal_one_version_one_file"))
+        .unwrap();
+    let (a, b) = compare_fixity(pair);
+    assert!(a.unwrap().is_empty());
+    assert!(b.is_err());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn compare_content_reports_copies_with_different_digests() {
+    let dir = unique_dir("compare_content");
+    copy_dir(Path::new("tests/cli/1.1"), &dir.join("a"));
+    copy_dir(Path::new("tests/cli/1.1"), &dir.join("b"));
+
+    // Replace the content of one copy and record its new digest, as a diverging replica would.
+    let object = dir.join("b/minimal_one_version_one_file");
+    let content = object.join("v1/content/a_file.txt");
+    let old = hex_digest(DigestAlgorithm::Sha512, fs::File::open(&content).unwrap()).unwrap();
+    fs::write(&content, "different").unwrap();
+    let new = hex_digest(DigestAlgorithm::Sha512, "different".as_bytes()).unwrap();
+    for inventory in ["inventory.json", "v1/inventory.json"] {
+        let json = fs::read_to_string(object.join(inventory)).unwrap();
+        fs::write(object.join(inventory), json.replace(&old, &new)).unwrap();
+    }
+
+    let comparison = compare_roots(dir.join("a"), dir.join("b"));
+    for pair in &comparison.pairs {
+        let differences = compare_content(pair).unwrap();
+        if pair.a.path.ends_with("minimal_one_version_one_file") {
+            assert_eq!(differences.len(), 1);
+            assert_eq!(differences[0].path, "v1/content/a_file.txt");
+            assert_eq!(differences[0].algorithm, "sha512");
+            assert_eq!((&differences[0].a, &differences[0].b), (&old, &new));
+        } else {
+            assert!(differences.is_empty());
+        }
+    }
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,87 @@
+use ocfl_crawler_rust::{backend::LocalStorage, crawl, crawl::crawl_in};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Copies the storage root fixture to `dir`, with its objects at "a/one", "b/two" and "c".
+fn create(dir: &Path) {
+    let fixture = Path::new("tests/cli/1.1");
+    fs::create_dir_all(dir).unwrap();
+    fs::copy(fixture.join("0=ocfl_1.1"), dir.join("0=ocfl_1.1")).unwrap();
+    copy_dir(&fixture.join("minimal_no_content"), &dir.join("b/two"));
+    copy_dir(
+        &fixture.join("minimal_one_version_one_file"),
+        &dir.join("a/one"),
+    );
+    copy_dir(
+        &fixture.join("minimal_one_version_one_file"),
+        &dir.join("c"),
+    );
+}
+
+#[test]
+fn crawls_local_directories_like_any_storage() {
+    let dir = unique_dir("crawl");
+    create(&dir);
+
+    let storage = crawl_in(&LocalStorage::new(&dir), "");
+    assert!(storage.errors.is_empty(), "{:?}", storage.errors);
+    assert_eq!(storage.objects, ["a/one", "b/two", "c"].map(PathBuf::from));
+
+    let result = crawl(&dir);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let expected: Vec<PathBuf> = storage.objects.iter().map(|o| dir.join(o)).collect();
+    assert_eq!(result.objects, expected);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[cfg(unix)]
+#[test]
+fn does_not_follow_symlinks() {
+    let dir = unique_dir("crawl_symlink");
+    create(&dir);
+    // A link back to the storage root would make a link-following walk loop forever.
+    std::os::unix::fs::symlink(fs::canonicalize(&dir).unwrap(), dir.join("a/loop")).unwrap();
+
+    let objects = ["a/one", "b/two", "c"];
+    assert_eq!(crawl(&dir).objects, objects.map(|o| dir.join(o)));
+    assert_eq!(
+        crawl_in(&LocalStorage::new(&dir), "").objects,
+        objects.map(PathBuf::from)
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
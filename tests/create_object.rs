@@ -0,0 +1,156 @@
+use ocfl_crawler_rust::{
+    check_fixity, check_version_consistency,
+    create::VersionInfo,
+    create_object,
+    digest::DigestAlgorithm,
+    inventory::{User, read_object_inventory, read_sidecar_digest},
+    is_object_root,
+    layout::Layout,
+    logical_files,
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+/// Creates a storage root using the hashed n-tuple layout, and a source directory to import.
+fn setup(dir: &Path) -> (PathBuf, PathBuf) {
+    let root = dir.join("root");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    fs::write(
+        root.join("ocfl_layout.json"),
+        r#"{"extension": "0004-hashed-n-tuple-storage-layout", "description": "test"}"#,
+    )
+    .unwrap();
+
+    let source = dir.join("source");
+    fs::create_dir_all(source.join("sub")).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    fs::write(source.join("copy.txt"), "alpha").unwrap();
+    fs::write(source.join("sub/b.txt"), "beta").unwrap();
+
+    (root, source)
+}
+
+#[test]
+fn creates_a_valid_object_at_the_layout_path() {
+    let dir = unique_dir("create_object");
+    let (root, source) = setup(&dir);
+
+    let info = VersionInfo {
+        message: Some(String::from("initial import")),
+        user: Some(User {
+            name: String::from("Alice"),
+            address: Some(String::from("mailto:alice@example.org")),
+        }),
+        created: Some(String::from("2024-05-01T10:00:00Z")),
+    };
+    let object = create_object(
+        &root,
+        "ark:/1234/x",
+        &source,
+        DigestAlgorithm::Sha256,
+        &info,
+    )
+    .unwrap();
+
+    let layout = Layout::from_config("0004-hashed-n-tuple-storage-layout", None).unwrap();
+    assert_eq!(
+        object,
+        root.join(layout.object_path("ark:/1234/x").unwrap())
+    );
+    assert!(is_object_root(&object));
+    assert_eq!(
+        fs::read_to_string(object.join("0=ocfl_object_1.1")).unwrap(),
+        "ocfl_object_1.1\n"
+    );
+
+    let inventory = read_object_inventory(&object).unwrap();
+    assert_eq!(inventory.id, "ark:/1234/x");
+    assert_eq!(inventory.type_, "https://ocfl.io/1.1/spec/#inventory");
+    assert_eq!(inventory.digest_algorithm, "sha256");
+    assert_eq!(inventory.head, "v1");
+    assert_eq!(inventory.manifest.len(), 2);
+    let version = inventory.version(None).unwrap();
+    assert_eq!(version.created, "2024-05-01T10:00:00Z");
+    assert_eq!(version.message.as_deref(), Some("initial import"));
+    assert_eq!(version.user, info.user);
+
+    let files = logical_files(&object, None).unwrap();
+    let paths: Vec<&str> = files.iter().map(|f| f.logical_path.as_str()).collect();
+    assert_eq!(paths, vec!["a.txt", "copy.txt", "sub/b.txt"]);
+    assert!(object.join("v1/content/sub/b.txt").is_file());
+
+    assert!(check_version_consistency(&object).unwrap().is_empty());
+    assert!(check_fixity(&object).unwrap().is_empty());
+    assert_eq!(
+        read_sidecar_digest(object.join("v1"), DigestAlgorithm::Sha256).unwrap(),
+        read_sidecar_digest(&object, DigestAlgorithm::Sha256).unwrap()
+    );
+
+    // Only the object itself is left below its parent; staging was renamed away.
+    let siblings: Vec<_> = fs::read_dir(object.parent().unwrap()).unwrap().collect();
+    assert_eq!(siblings.len(), 1);
+
+    let err = create_object(
+        &root,
+        "ark:/1234/x",
+        &source,
+        DigestAlgorithm::Sha256,
+        &info,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn rejects_bad_input() {
+    let dir = unique_dir("create_object_errors");
+    let (root, source) = setup(&dir);
+    let info = VersionInfo::default();
+
+    let err = create_object(&source, "x", &source, DigestAlgorithm::Sha512, &info).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = create_object(
+        &root,
+        "x",
+        dir.join("missing"),
+        DigestAlgorithm::Sha512,
+        &info,
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = create_object(&root, "x", &source, DigestAlgorithm::Md5, &info).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(root.join("ocfl_layout.json")).unwrap();
+    let err = create_object(&root, "x", &source, DigestAlgorithm::Sha512, &info).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,33 @@
+use ocfl_crawler_rust::diff_versions;
+
+const OBJECT: &str = "tests/ocfl/1.1/good-objects/spec-ex-full";
+
+#[test]
+fn reports_added_removed_and_modified_files() {
+    let diff = diff_versions(OBJECT, "v1", "v2").expect("diff failed");
+
+    let added: Vec<&str> = diff.added.iter().map(|c| c.path.as_str()).collect();
+    let removed: Vec<&str> = diff.removed.iter().map(|c| c.path.as_str()).collect();
+    let modified: Vec<&str> = diff.modified.iter().map(|m| m.path.as_str()).collect();
+
+    assert_eq!(added, vec!["empty2.txt"]);
+    assert_eq!(removed, vec!["image.tiff"]);
+    assert_eq!(modified, vec!["foo/bar.xml"]);
+    assert!(diff.renamed.is_empty());
+}
+
+#[test]
+fn reports_renamed_files() {
+    let diff = diff_versions(OBJECT, "v2", "v3").expect("diff failed");
+
+    assert_eq!(diff.renamed.len(), 1);
+    assert_eq!(diff.renamed[0].from, "empty2.txt");
+    assert_eq!(diff.renamed[0].to, "docs/empty2.txt");
+    assert!(diff.removed.is_empty());
+}
+
+#[test]
+fn identical_versions_have_an_empty_diff() {
+    assert!(diff_versions(OBJECT, "v3", "v3").unwrap().is_empty());
+    assert!(diff_versions(OBJECT, "v1", "v9").is_err());
+}
@@ -0,0 +1,125 @@
+use ocfl_crawler_rust::{
+    check_fixity,
+    create::VersionInfo,
+    create_object,
+    digest::{DigestAlgorithm, file_digest, hex_digest},
+    inventory::{read_object_inventory, write_inventory},
+    layout::Layout,
+    storage::init_storage_root,
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+#[test]
+fn computes_every_supported_algorithm() {
+    let digest = |algorithm: &str| hex_digest(algorithm.parse().unwrap(), &b"abc"[..]).unwrap();
+
+    assert_eq!(digest("md5"), "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(digest("sha1"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(
+        digest("sha256"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        digest("sha512/256"),
+        "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"
+    );
+    assert_eq!(
+        digest("blake2b-512"),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+    assert_eq!(
+        digest("blake2b-256"),
+        "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319"
+    );
+    assert_eq!(digest("blake2b-160").len(), 40);
+    assert_eq!(digest("blake2b-384").len(), 96);
+    assert_eq!(digest("size"), "3");
+}
+
+#[test]
+fn parses_algorithm_names() {
+    for algorithm in DigestAlgorithm::ALL {
+        assert_eq!(
+            algorithm.name().parse::<DigestAlgorithm>().unwrap(),
+            algorithm
+        );
+        assert_eq!(
+            algorithm.is_core(),
+            matches!(algorithm, DigestAlgorithm::Sha512 | DigestAlgorithm::Sha256)
+        );
+    }
+    let err = "crc32".parse::<DigestAlgorithm>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    let err = DigestAlgorithm::Md5.require_core().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn checks_fixity_blocks_and_core_inventory_algorithms() {
+    let dir = unique_dir("digest_algorithms");
+    let root = dir.join("root");
+    init_storage_root(&root, "1.1", &Layout::FlatDirect).unwrap();
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    let object = create_object(
+        &root,
+        "obj",
+        &source,
+        DigestAlgorithm::Sha512,
+        &VersionInfo::default(),
+    )
+    .unwrap();
+
+    let mut inventory = read_object_inventory(&object).unwrap();
+    let content_path = String::from("v1/content/a.txt");
+    for algorithm in [DigestAlgorithm::Md5, DigestAlgorithm::Size] {
+        let digest = file_digest(algorithm, object.join(&content_path)).unwrap();
+        inventory.fixity.insert(
+            algorithm.to_string(),
+            [(digest, vec![content_path.clone()])].into(),
+        );
+    }
+    write_inventory(&object, &inventory).unwrap();
+    assert!(check_fixity(&object).unwrap().is_empty());
+
+    fs::write(object.join(&content_path), "alphabet").unwrap();
+    let mut algorithms: Vec<String> = check_fixity(&object)
+        .unwrap()
+        .into_iter()
+        .map(|issue| issue.algorithm)
+        .collect();
+    algorithms.sort();
+    assert_eq!(algorithms, vec!["md5", "sha512", "size"]);
+
+    inventory.digest_algorithm = String::from("md5");
+    let err = write_inventory(&object, &inventory).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    fs::remove_dir_all(&dir).ok();
+}
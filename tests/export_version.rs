@@ -0,0 +1,143 @@
+use ocfl_crawler_rust::{
+    digest::{DigestAlgorithm, hex_digest},
+    export_version,
+    inventory::{DigestMap, Inventory, Version, write_inventory},
+    open_logical_file,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{ErrorKind, Read},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const OBJECT: &str = "tests/ocfl/1.1/good-objects/spec-ex-full";
+
+fn unique_dir(prefix: &str) -> std::path::PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+#[test]
+fn open_logical_file_reads_requested_version() {
+    let mut contents = String::new();
+    open_logical_file(OBJECT, Some("v1"), "foo/bar.xml")
+        .expect("failed to open foo/bar.xml")
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "<foo>bar</foo>\n");
+
+    assert!(open_logical_file(OBJECT, None, "missing.txt").is_err());
+}
+
+#[test]
+fn export_version_materializes_logical_state() {
+    let dest = unique_dir("export_version");
+
+    let files = export_version(OBJECT, None, &dest).expect("export failed");
+    assert_eq!(files.len(), 4);
+    assert_eq!(
+        fs::read_to_string(dest.join("foo/bar.xml")).unwrap(),
+        "<foo>baz</foo>\n"
+    );
+    assert!(dest.join("docs/empty2.txt").is_file());
+
+    // Exporting into a non-empty directory is refused.
+    assert!(export_version(OBJECT, None, &dest).is_err());
+
+    // Cleanup
+    let _ = fs::remove_dir_all(&dest);
+}
+
+#[test]
+fn export_version_detects_digest_mismatch() {
+    let dest = unique_dir("export_version_mismatch");
+
+    let result = export_version(
+        "tests/ocfl/1.1/bad-objects/E092_content_digest_mismatch",
+        None,
+        &dest,
+    );
+    assert!(result.is_err(), "tampered content must not be exported");
+    assert!(!dest.join("image.tiff").exists());
+
+    // Cleanup
+    let _ = fs::remove_dir_all(&dest);
+}
+
+/// Writes an object at `dir/object` whose v1 holds "alpha" under `logical_paths`, stored at
+/// the manifest path `content_path`.
+fn write_object(dir: &Path, content_path: &str, logical_paths: &[&str]) -> PathBuf {
+    let object = dir.join("object");
+    fs::create_dir_all(object.join("v1/content")).unwrap();
+    fs::write(object.join("0=ocfl_object_1.1"), "ocfl_object_1.1\n").unwrap();
+    fs::write(object.join("v1/content/a.txt"), "alpha").unwrap();
+    fs::write(dir.join("secret.txt"), "alpha").unwrap();
+
+    let digest = hex_digest(DigestAlgorithm::Sha512, &b"alpha"[..]).unwrap();
+    let manifest = DigestMap::from([(digest.clone(), vec![content_path.to_string()])]);
+    let state = DigestMap::from([(
+        digest,
+        logical_paths.iter().map(|p| p.to_string()).collect(),
+    )]);
+    let inventory = Inventory {
+        id: String::from("obj"),
+        type_: String::from("https://ocfl.io/1.1/spec/#inventory"),
+        digest_algorithm: String::from("sha512"),
+        head: String::from("v1"),
+        content_directory: None,
+        manifest,
+        versions: BTreeMap::from([(
+            String::from("v1"),
+            Version {
+                created: String::from("2020-01-01T00:00:00Z"),
+                message: None,
+                user: None,
+                state,
+            },
+        )]),
+        fixity: BTreeMap::new(),
+    };
+    write_inventory(&object, &inventory).unwrap();
+    object
+}
+
+#[test]
+fn refuses_content_paths_outside_the_object() {
+    let dir = unique_dir("export_version_content_path");
+    let object = write_object(&dir, "../secret.txt", &["a.txt"]);
+
+    let err = open_logical_file(&object, None, "a.txt").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let err = export_version(&object, None, dir.join("export")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn refuses_unsafe_logical_paths_before_copying() {
+    let dir = unique_dir("export_version_logical_path");
+    let object = write_object(&dir, "v1/content/a.txt", &["a.txt", "../b.txt"]);
+
+    let dest = dir.join("export");
+    let err = export_version(&object, None, &dest).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(!dest.join("a.txt").exists());
+    assert!(!dir.join("b.txt").exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
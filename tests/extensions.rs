@@ -0,0 +1,101 @@
+use ocfl_crawler_rust::{
+    create::VersionInfo,
+    create_object,
+    digest::DigestAlgorithm,
+    extensions::{is_registered_extension, object_extensions, storage_root_extensions},
+    layout::Layout,
+    storage::init_storage_root,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+#[test]
+fn reports_extensions_across_a_storage_root() {
+    let dir = unique_dir("extensions");
+    let root = dir.join("root");
+    init_storage_root(&root, "1.1", &Layout::FlatDirect).unwrap();
+
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    let mut objects = Vec::new();
+    for id in ["a", "b", "c"] {
+        objects.push(
+            create_object(
+                &root,
+                id,
+                &source,
+                DigestAlgorithm::Sha512,
+                &VersionInfo::default(),
+            )
+            .unwrap(),
+        );
+    }
+    fs::create_dir_all(objects[0].join("extensions/0005-mutable-head/head")).unwrap();
+    fs::create_dir_all(objects[1].join("extensions/0005-mutable-head")).unwrap();
+    fs::create_dir_all(objects[1].join("extensions/9999-local-notes")).unwrap();
+    fs::write(objects[1].join("extensions/stray.txt"), "").unwrap();
+
+    let report = storage_root_extensions(&root).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(report.root, vec!["0002-flat-direct-storage-layout"]);
+    assert_eq!(
+        report.objects,
+        vec![
+            (objects[0].clone(), vec![String::from("0005-mutable-head")]),
+            (
+                objects[1].clone(),
+                vec![
+                    String::from("0005-mutable-head"),
+                    String::from("9999-local-notes")
+                ]
+            ),
+        ]
+    );
+    assert_eq!(report.counts["0005-mutable-head"], 2);
+    assert_eq!(report.counts["0002-flat-direct-storage-layout"], 1);
+    assert_eq!(report.counts["9999-local-notes"], 1);
+
+    let mut codes: Vec<&str> = report.issues.iter().map(|issue| issue.code).collect();
+    codes.sort();
+    assert_eq!(codes, vec!["E067", "W013"]);
+
+    let (names, issues) = object_extensions(&objects[2]).unwrap();
+    assert!(names.is_empty() && issues.is_empty());
+    assert!(object_extensions(&root).is_err());
+    assert!(storage_root_extensions(&objects[0]).is_err());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn knows_registered_extensions() {
+    assert!(is_registered_extension("0001-digest-algorithms"));
+    assert!(is_registered_extension(
+        "0004-hashed-n-tuple-storage-layout"
+    ));
+    assert!(!is_registered_extension("0004-hashed-n-tuple"));
+    assert!(!is_registered_extension("local"));
+}
@@ -0,0 +1,24 @@
+use ocfl_crawler_rust::find_duplicates;
+
+#[test]
+fn finds_identifiers_shared_across_storage_roots() {
+    let duplicates = find_duplicates(&["tests/cli/1.0", "tests/cli/1.1"]);
+
+    assert!(duplicates.errors.is_empty());
+    let ids: Vec<&str> = duplicates.groups.iter().map(|g| g.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec!["ark:123/abc", "http://example.org/minimal_no_content"]
+    );
+
+    let group = &duplicates.groups[0];
+    assert_eq!(group.objects.len(), 2);
+    assert!(group.heads_match());
+    // The 1.0 and 1.1 copies have different inventory "type" values.
+    assert!(!group.inventories_match());
+}
+
+#[test]
+fn single_storage_root_has_no_duplicates() {
+    assert!(find_duplicates(&["tests/cli/1.1"]).groups.is_empty());
+}
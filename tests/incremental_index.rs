@@ -0,0 +1,106 @@
+use ocfl_crawler_rust::index::{ChangeKind, Index};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+fn kinds(events: &[ocfl_crawler_rust::index::ChangeEvent]) -> Vec<(ChangeKind, String)> {
+    let mut kinds: Vec<_> = events
+        .iter()
+        .map(|e| {
+            let name = e.path.file_name().unwrap().to_string_lossy().to_string();
+            (e.kind, name)
+        })
+        .collect();
+    kinds.sort_by(|a, b| a.1.cmp(&b.1));
+    kinds
+}
+
+#[test]
+fn reports_created_updated_and_deleted_objects() {
+    let root = unique_dir("incremental");
+    copy_dir(Path::new("tests/cli/1.1"), &root);
+
+    let mut index = Index::open_in_memory().expect("failed to open index");
+
+    let first = index.update_storage_root(&root).unwrap();
+    assert_eq!(
+        kinds(&first.events),
+        vec![
+            (ChangeKind::Created, String::from("minimal_no_content")),
+            (
+                ChangeKind::Created,
+                String::from("minimal_one_version_one_file")
+            ),
+        ]
+    );
+
+    let unchanged = index.update_storage_root(&root).unwrap();
+    assert!(unchanged.events.is_empty());
+    assert_eq!(unchanged.unchanged, 2);
+
+    // A rewritten sidecar marks the object as changed even if its mtime is not.
+    let sidecar = root.join("minimal_no_content/inventory.json.sha512");
+    fs::write(&sidecar, "0000 inventory.json\n").unwrap();
+    fs::remove_dir_all(root.join("minimal_one_version_one_file")).unwrap();
+    copy_dir(
+        Path::new("tests/ocfl/1.1/good-objects/spec-ex-full"),
+        &root.join("spec-ex-full"),
+    );
+
+    let feed = index.update_storage_root(&root).unwrap();
+    assert_eq!(
+        kinds(&feed.events),
+        vec![
+            (ChangeKind::Updated, String::from("minimal_no_content")),
+            (
+                ChangeKind::Deleted,
+                String::from("minimal_one_version_one_file")
+            ),
+            (ChangeKind::Created, String::from("spec-ex-full")),
+        ]
+    );
+    assert_eq!(feed.unchanged, 0);
+    let created = feed
+        .events
+        .iter()
+        .find(|e| e.kind == ChangeKind::Created)
+        .unwrap();
+    assert_eq!(created.id, "ark:123/spec-ex-full");
+    assert_eq!(created.head, "v3");
+
+    assert_eq!(index.query(&Default::default()).unwrap().len(), 2);
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,188 @@
+use ocfl_crawler_rust::{
+    index::{Index, Query},
+    inventory::{read_object_inventory, write_inventory},
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Rewrites the `created` timestamp of every version in the root inventory of `object`.
+fn set_created(object: &Path, created: &str) {
+    let mut inventory = read_object_inventory(object).unwrap();
+    for version in inventory.versions.values_mut() {
+        version.created = created.to_string();
+    }
+    write_inventory(object, &inventory).unwrap();
+}
+
+#[test]
+fn indexes_storage_roots_and_answers_queries() {
+    let mut index = Index::open_in_memory().expect("failed to open index");
+
+    for root in ["tests/cli/1.0", "tests/cli/1.1"] {
+        let report = index.index_storage_root(root).expect("indexing failed");
+        assert_eq!(report.indexed, 2);
+        assert!(report.errors.is_empty());
+    }
+
+    let by_id = index
+        .query(&Query {
+            id: Some(String::from("ark:123/abc")),
+            ..Query::default()
+        })
+        .unwrap();
+    assert_eq!(by_id.len(), 2);
+    assert!(
+        by_id
+            .iter()
+            .all(|e| e.head == "v1" && e.physical_bytes == 20)
+    );
+
+    let by_spec = index
+        .query(&Query {
+            spec_version: Some(String::from("1.0")),
+            ..Query::default()
+        })
+        .unwrap();
+    assert_eq!(by_spec.len(), 2);
+
+    let root = Path::new("tests/cli/1.1").canonicalize().unwrap();
+    let by_root = index
+        .query(&Query {
+            storage_root: Some(root.clone()),
+            ..Query::default()
+        })
+        .unwrap();
+    assert!(by_root.iter().all(|e| e.storage_root == root));
+    assert_eq!(by_root.len(), 2);
+}
+
+#[test]
+fn filters_on_version_timestamps() {
+    let mut index = Index::open_in_memory().expect("failed to open index");
+    index.index_storage_root("tests/cli/1.1").unwrap();
+
+    let before = Query {
+        created_before: Some(String::from("2019-01-01T00:00:00Z")),
+        ..Query::default()
+    };
+    assert!(index.query(&before).unwrap().is_empty());
+
+    let after = Query {
+        created_after: Some(String::from("2019-01-01T00:00:00Z")),
+        ..Query::default()
+    };
+    assert_eq!(index.query(&after).unwrap().len(), 2);
+
+    // Re-indexing replaces entries instead of duplicating them.
+    let report = index.index_storage_root("tests/cli/1.1").unwrap();
+    assert_eq!(report.removed, 0);
+    assert_eq!(index.query(&Query::default()).unwrap().len(), 2);
+}
+
+#[test]
+fn compares_version_timestamps_in_utc() {
+    let dir = unique_dir("index_utc");
+    copy_dir(Path::new("tests/cli/1.1"), &dir);
+    set_created(&dir.join("minimal_no_content"), "2020-01-01T01:30:00+02:00");
+    set_created(
+        &dir.join("minimal_one_version_one_file"),
+        "2019-12-31T20:00:00-05:00",
+    );
+
+    let mut index = Index::open_in_memory().expect("failed to open index");
+    index.index_storage_root(&dir).unwrap();
+
+    // minimal_no_content was created at 23:30 UTC, the other object at 01:00 UTC the next day.
+    let ids = |query: Query| -> Vec<String> {
+        index
+            .query(&query)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect()
+    };
+    assert_eq!(
+        ids(Query {
+            created_before: Some(String::from("2020-01-01T00:00:00Z")),
+            ..Query::default()
+        }),
+        ["http://example.org/minimal_no_content"]
+    );
+    assert_eq!(
+        ids(Query {
+            created_after: Some(String::from("2020-01-01T02:00:00+02:00")),
+            ..Query::default()
+        }),
+        ["ark:123/abc"]
+    );
+
+    let err = index
+        .query(&Query {
+            created_after: Some(String::from("yesterday")),
+            ..Query::default()
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn malformed_timestamps_leave_no_entry_behind() {
+    let dir = unique_dir("index_bad_created");
+    copy_dir(Path::new("tests/cli/1.1"), &dir);
+    set_created(&dir.join("minimal_no_content"), "not-a-date");
+
+    let mut index = Index::open_in_memory().expect("failed to open index");
+    let report = index.index_storage_root(&dir).unwrap();
+    assert_eq!(report.indexed, 1);
+    assert_eq!(report.errors.len(), 1);
+    let ids: Vec<String> = index
+        .query(&Query::default())
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.id)
+        .collect();
+    assert_eq!(ids, ["ark:123/abc"]);
+
+    // The broken object is retried, and fails again, instead of counting as unchanged.
+    let feed = index.update_storage_root(&dir).unwrap();
+    assert_eq!(feed.unchanged, 1);
+    assert_eq!(feed.errors.len(), 1);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,107 @@
+use ocfl_crawler_rust::{
+    create::VersionInfo, create_object, digest::DigestAlgorithm, is_object_root, is_storage_root,
+    layout::Layout, storage::init_storage_root,
+};
+use serde_json::json;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+#[test]
+fn initializes_a_storage_root_with_its_layout() {
+    let dir = unique_dir("init_root");
+    let root = dir.join("root");
+    let layout = Layout::from_config(
+        "0004-hashed-n-tuple-storage-layout",
+        Some(&json!({"tupleSize": 2, "numberOfTuples": 2, "shortObjectRoot": true})),
+    )
+    .unwrap();
+
+    init_storage_root(&root, "1.1", &layout).unwrap();
+    assert!(is_storage_root(&root));
+    assert!(root.join("ocfl_1.1.md").is_file());
+    assert_eq!(
+        Layout::from_storage_root(&root).unwrap(),
+        Some(layout.clone())
+    );
+
+    let config: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(root.join("extensions/0004-hashed-n-tuple-storage-layout/config.json"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        config,
+        json!({
+            "extensionName": "0004-hashed-n-tuple-storage-layout",
+            "digestAlgorithm": "sha256",
+            "tupleSize": 2,
+            "numberOfTuples": 2,
+            "shortObjectRoot": true,
+        })
+    );
+
+    // New objects land where the layout puts them.
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    let object = create_object(
+        &root,
+        "obj-1",
+        &source,
+        DigestAlgorithm::Sha512,
+        &VersionInfo::default(),
+    )
+    .unwrap();
+    assert!(is_object_root(&object));
+    assert_eq!(object, root.join(layout.object_path("obj-1").unwrap()));
+
+    let err = init_storage_root(&root, "1.1", &layout).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn initializes_existing_empty_directories_only() {
+    let dir = unique_dir("init_root_existing");
+    let layout = Layout::FlatDirect;
+
+    init_storage_root(&dir, "1.0", &layout).unwrap();
+    assert!(is_storage_root(&dir));
+    assert!(dir.join("ocfl_1.0.txt").is_file());
+    assert_eq!(Layout::from_storage_root(&dir).unwrap(), Some(layout));
+
+    let other = dir.join("other");
+    fs::create_dir_all(&other).unwrap();
+    fs::write(other.join("stray.txt"), "").unwrap();
+    let err = init_storage_root(&other, "1.1", &Layout::FlatDirect).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    let err = init_storage_root(dir.join("new"), "2.0", &Layout::FlatDirect).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(!dir.join("new").exists());
+
+    fs::remove_dir_all(&dir).ok();
+}
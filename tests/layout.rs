@@ -0,0 +1,76 @@
+use ocfl_crawler_rust::layout::Layout;
+use serde_json::json;
+
+#[test]
+fn hashed_n_tuple_matches_extension_examples() {
+    let layout = Layout::from_config("0004-hashed-n-tuple-storage-layout", None).unwrap();
+    assert_eq!(
+        layout.object_path("object-01").unwrap(),
+        "3c0/ff4/240/3c0ff4240c1e116dba14c7627f2319b58aa3d77606d0d90dfc6161608ac987d4"
+    );
+    assert_eq!(
+        layout.object_path("..hor/rib:le-$id").unwrap(),
+        "487/326/d8c/487326d8c2a3c0b885e23da1469b4d6671fd4e76978924b4443e9e3c316cda6d"
+    );
+
+    let short = Layout::from_config(
+        "0004-hashed-n-tuple-storage-layout",
+        Some(&json!({"tupleSize": 2, "numberOfTuples": 15, "shortObjectRoot": true})),
+    )
+    .unwrap();
+    assert_eq!(
+        short.object_path("object-01").unwrap(),
+        "3c/0f/f4/24/0c/1e/11/6d/ba/14/c7/62/7f/23/19/b58aa3d77606d0d90dfc6161608ac987d4"
+    );
+}
+
+#[test]
+fn hash_and_id_n_tuple_matches_extension_examples() {
+    let layout = Layout::from_config("0003-hash-and-id-n-tuple-storage-layout", None).unwrap();
+    assert_eq!(
+        layout.object_path("object-01").unwrap(),
+        "3c0/ff4/240/object-01"
+    );
+    assert_eq!(
+        layout.object_path("..hor/rib:le-$id").unwrap(),
+        "487/326/d8c/%2e%2ehor%2frib%3ale-%24id"
+    );
+}
+
+#[test]
+fn flat_layouts_use_the_identifier() {
+    assert_eq!(
+        Layout::FlatDirect.object_path("object-01").unwrap(),
+        "object-01"
+    );
+    assert!(Layout::FlatDirect.object_path("a/b").is_err());
+
+    let omit = Layout::from_config(
+        "0006-flat-omit-prefix-storage-layout",
+        Some(&json!({"delimiter": ":"})),
+    )
+    .unwrap();
+    assert_eq!(
+        omit.object_path("urn:uuid:6e8bc430-9c3a-11d9-9669-0800200c9a66")
+            .unwrap(),
+        "6e8bc430-9c3a-11d9-9669-0800200c9a66"
+    );
+    assert!(Layout::from_config("0006-flat-omit-prefix-storage-layout", None).is_err());
+}
+
+#[test]
+fn reads_layout_from_storage_root() {
+    let layout = Layout::from_storage_root("tests/roots/0004-hashed-n-tuple")
+        .unwrap()
+        .expect("storage root declares a layout");
+    assert_eq!(
+        layout.extension_name(),
+        "0004-hashed-n-tuple-storage-layout"
+    );
+    assert!(
+        Layout::from_storage_root("tests/cli/1.1")
+            .unwrap()
+            .is_none()
+    );
+    assert!(Layout::from_config("9999-unknown", None).is_err());
+}
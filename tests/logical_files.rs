@@ -0,0 +1,37 @@
+use ocfl_crawler_rust::logical_files;
+
+const OBJECT: &str = "tests/ocfl/1.1/good-objects/spec-ex-full";
+
+#[test]
+fn lists_head_version_by_default() {
+    let files = logical_files(OBJECT, None).expect("failed to list logical files");
+    let paths: Vec<&str> = files.iter().map(|f| f.logical_path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec!["docs/empty2.txt", "empty.txt", "foo/bar.xml", "image.tiff"]
+    );
+
+    // image.tiff was removed in v2 and reinstated in v3 from the v1 content.
+    let image = files
+        .iter()
+        .find(|f| f.logical_path == "image.tiff")
+        .unwrap();
+    assert_eq!(image.content_path, "v1/content/image.tiff");
+    assert_eq!(image.size, Some(18));
+}
+
+#[test]
+fn lists_requested_version() {
+    let files = logical_files(OBJECT, Some("v2")).expect("failed to list logical files");
+    let bar = files
+        .iter()
+        .find(|f| f.logical_path == "foo/bar.xml")
+        .unwrap();
+    assert_eq!(bar.content_path, "v2/content/foo/bar.xml");
+    assert!(files.iter().all(|f| f.logical_path != "image.tiff"));
+}
+
+#[test]
+fn unknown_version_is_an_error() {
+    assert!(logical_files(OBJECT, Some("v9")).is_err());
+}
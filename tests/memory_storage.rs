@@ -0,0 +1,143 @@
+use ocfl_crawler_rust::{
+    backend::{EntryKind, Storage},
+    crawl::crawl_in,
+    digest::DigestAlgorithm,
+    inventory::read_object_inventory_in,
+    memory::{MemoryStorage, ObjectBuilder, StorageRootBuilder, VersionBuilder},
+    object::{get_object_id_in, get_object_spec_version_in, is_object_root_in},
+    storage::is_storage_root_in,
+    validate::check_version_consistency_in,
+};
+use std::path::PathBuf;
+
+fn two_versions(id: &str) -> ObjectBuilder {
+    ObjectBuilder::new(id)
+        .version(
+            VersionBuilder::new()
+                .file("a.txt", "alpha")
+                .file("dir/b.txt", "beta")
+                .message("first")
+                .user("Alice", Some("mailto:alice@example.org")),
+        )
+        .version(
+            VersionBuilder::new()
+                .file("a.txt", "alpha")
+                .file("copy.txt", "beta")
+                .created("2021-06-01T12:00:00Z"),
+        )
+}
+
+#[test]
+fn builds_a_crawlable_storage_root() {
+    let storage = StorageRootBuilder::new()
+        .object("objects/one", two_versions("urn:example:one"))
+        .object(
+            "objects/two",
+            ObjectBuilder::new("urn:example:two")
+                .spec_version("1.0")
+                .digest_algorithm(DigestAlgorithm::Sha256)
+                .version(VersionBuilder::new().file("x", "y")),
+        )
+        .file("README.txt", "not an object")
+        .build()
+        .unwrap();
+
+    assert!(is_storage_root_in(&storage, ""));
+    let result = crawl_in(&storage, "");
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(
+        result.objects,
+        vec![PathBuf::from("objects/one"), PathBuf::from("objects/two")]
+    );
+
+    assert!(is_object_root_in(&storage, "objects/one"));
+    assert_eq!(
+        get_object_id_in(&storage, "objects/one").unwrap(),
+        "urn:example:one"
+    );
+    assert_eq!(
+        get_object_spec_version_in(&storage, "objects/two").unwrap(),
+        "1.0"
+    );
+    assert!(storage.is_file("objects/two/inventory.json.sha256"));
+
+    for object in ["objects/one", "objects/two"] {
+        assert!(
+            check_version_consistency_in(&storage, object)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
+
+#[test]
+fn deduplicates_content_across_versions() {
+    let object = two_versions("urn:example:one");
+    let inventory = object.inventory().unwrap();
+    assert_eq!(inventory.head, "v2");
+    assert_eq!(inventory.manifest.len(), 2);
+    assert_eq!(inventory.versions["v1"].message.as_deref(), Some("first"));
+    assert_eq!(inventory.versions["v2"].created, "2021-06-01T12:00:00Z");
+
+    let mut storage = MemoryStorage::new();
+    object.write_to(&mut storage, "").unwrap();
+    assert_eq!(read_object_inventory_in(&storage, "").unwrap(), inventory);
+    assert_eq!(
+        storage.read_to_string("v1/content/dir/b.txt").unwrap(),
+        "beta"
+    );
+    assert!(!storage.is_dir("v2/content"));
+    assert!(storage.is_file("v1/inventory.json"));
+    assert!(storage.is_file("v1/inventory.json.sha512"));
+}
+
+#[test]
+fn builds_faulty_objects_on_request() {
+    let mut storage = MemoryStorage::new();
+    two_versions("urn:example:one")
+        .without_sidecars()
+        .without_version_inventories()
+        .write_to(&mut storage, "obj")
+        .unwrap();
+    assert!(!storage.is_file("obj/inventory.json.sha512"));
+    let codes: Vec<&str> = check_version_consistency_in(&storage, "obj")
+        .unwrap()
+        .iter()
+        .map(|issue| issue.code)
+        .collect();
+    assert_eq!(codes, vec!["W010", "W010"]);
+
+    storage.remove("obj/v2");
+    assert!(!storage.is_dir("obj/v2"));
+    assert!(
+        check_version_consistency_in(&storage, "obj")
+            .unwrap()
+            .iter()
+            .any(|issue| issue.code == "E046")
+    );
+
+    storage.insert_file("obj/0=ocfl_object_1.0", "ocfl_object_1.0\n");
+    assert!(!is_object_root_in(&storage, "obj"));
+}
+
+#[test]
+fn lists_and_reads_entries() {
+    let mut storage = MemoryStorage::new();
+    storage.insert_file("/a/b/c.txt", "hello");
+    storage.create_dir("a/empty");
+
+    let entries = storage.read_dir("a").unwrap();
+    let names: Vec<(&str, EntryKind)> = entries.iter().map(|e| (e.name.as_str(), e.kind)).collect();
+    assert_eq!(
+        names,
+        vec![("b", EntryKind::Dir), ("empty", EntryKind::Dir)]
+    );
+    assert_eq!(storage.metadata("a/b/c.txt").unwrap().size, 5);
+    assert_eq!(storage.read_to_string("a/b/c.txt").unwrap(), "hello");
+    assert!(storage.read_dir("a/b/c.txt").is_err());
+    assert!(storage.open("a/b").is_err());
+    assert!(storage.metadata("missing").is_err());
+    assert!(storage.remove("a"));
+    assert!(!storage.remove("a"));
+    assert!(storage.read_dir("").unwrap().is_empty());
+}
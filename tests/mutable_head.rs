@@ -0,0 +1,134 @@
+use ocfl_crawler_rust::{
+    backend::LocalStorage,
+    create::VersionInfo,
+    create_object,
+    digest::{DigestAlgorithm, hex_digest},
+    inventory::{read_object_inventory, write_inventory},
+    logical_files,
+    mutable_head::{
+        MUTABLE_HEAD_EXTENSION, export_mutable_head, mutable_head_dir, mutable_head_files,
+        read_mutable_head, read_mutable_head_in,
+    },
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+/// Creates a one-version object, returning its root.
+fn create(dir: &Path) -> PathBuf {
+    let root = dir.join("root");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    fs::write(
+        root.join("ocfl_layout.json"),
+        r#"{"extension": "0002-flat-direct-storage-layout", "description": "test"}"#,
+    )
+    .unwrap();
+
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    create_object(
+        &root,
+        "obj",
+        &source,
+        DigestAlgorithm::Sha512,
+        &VersionInfo::default(),
+    )
+    .unwrap()
+}
+
+/// Adds a mutable head `v2` in two revisions: r1 adds b.txt, r2 adds c.txt.
+fn add_mutable_head(object: &Path) {
+    let mut inventory = read_object_inventory(object).unwrap();
+    let mut state = inventory.versions["v1"].clone();
+    let head_dir = mutable_head_dir(object);
+    for (revision, name, contents) in [("r1", "b.txt", "beta"), ("r2", "c.txt", "gamma")] {
+        let content_path =
+            format!("extensions/{MUTABLE_HEAD_EXTENSION}/head/content/{revision}/{name}");
+        fs::create_dir_all(object.join(&content_path).parent().unwrap()).unwrap();
+        fs::write(object.join(&content_path), contents).unwrap();
+        let digest = hex_digest(DigestAlgorithm::Sha512, contents.as_bytes()).unwrap();
+        inventory
+            .manifest
+            .insert(digest.clone(), vec![content_path]);
+        state.state.insert(digest, vec![name.to_string()]);
+    }
+    inventory.versions.insert(String::from("v2"), state);
+    inventory.head = String::from("v2");
+    write_inventory(&head_dir, &inventory).unwrap();
+
+    let revisions = object
+        .join("extensions")
+        .join(MUTABLE_HEAD_EXTENSION)
+        .join("revisions");
+    fs::create_dir_all(&revisions).unwrap();
+    fs::write(revisions.join("r1"), "r1").unwrap();
+}
+
+#[test]
+fn detects_lists_and_exports_the_mutable_head() {
+    let dir = unique_dir("mutable_head");
+    let object = create(&dir);
+    assert_eq!(read_mutable_head(&object).unwrap(), None);
+    let err = mutable_head_files(&object).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    add_mutable_head(&object);
+    let head = read_mutable_head(&object).unwrap().unwrap();
+    assert_eq!(head.version, "v2");
+    assert_eq!(head.revision, Some(2));
+    let storage = LocalStorage::new(dir.join("root"));
+    let object_path = object.strip_prefix(dir.join("root")).unwrap();
+    assert_eq!(
+        read_mutable_head_in(&storage, &object_path.to_string_lossy()).unwrap(),
+        Some(head)
+    );
+
+    // The committed head is unchanged.
+    let committed: Vec<String> = logical_files(&object, None)
+        .unwrap()
+        .into_iter()
+        .map(|f| f.logical_path)
+        .collect();
+    assert_eq!(committed, vec!["a.txt"]);
+
+    let files = mutable_head_files(&object).unwrap();
+    let paths: Vec<&str> = files.iter().map(|f| f.logical_path.as_str()).collect();
+    assert_eq!(paths, vec!["a.txt", "b.txt", "c.txt"]);
+    assert_eq!(files[0].content_path, "v1/content/a.txt");
+    assert_eq!(
+        files[2].content_path,
+        "extensions/0005-mutable-head/head/content/r2/c.txt"
+    );
+    assert_eq!(files[2].size, Some(5));
+
+    let dest = dir.join("export");
+    export_mutable_head(&object, &dest).unwrap();
+    assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "alpha");
+    assert_eq!(fs::read_to_string(dest.join("c.txt")).unwrap(), "gamma");
+
+    fs::remove_dir_all(&dir).ok();
+}
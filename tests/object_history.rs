@@ -0,0 +1,24 @@
+use ocfl_crawler_rust::object_history;
+
+#[test]
+fn summarizes_versions_oldest_first() {
+    let history =
+        object_history("tests/ocfl/1.1/good-objects/spec-ex-full").expect("failed to read history");
+
+    let versions: Vec<&str> = history.iter().map(|s| s.version.as_str()).collect();
+    assert_eq!(versions, vec!["v1", "v2", "v3"]);
+
+    assert_eq!(history[0].added, 3);
+    assert_eq!(history[0].message.as_deref(), Some("Initial import"));
+    assert_eq!(history[1].user.as_ref().unwrap().name, "B Person");
+    assert_eq!(
+        (history[1].added, history[1].removed, history[1].modified),
+        (1, 1, 1)
+    );
+    assert_eq!((history[2].added, history[2].renamed), (1, 1));
+}
+
+#[test]
+fn not_an_object_root_is_rejected() {
+    assert!(object_history("tests/ocfl").is_err());
+}
@@ -0,0 +1,118 @@
+use ocfl_crawler_rust::{
+    ObjectVersion,
+    digest::{DigestAlgorithm, hex_digest},
+    inventory::{DigestMap, Inventory, Version, read_object_inventory, write_inventory},
+    logical_files,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+/// Writes a two-version object with contentDirectory "data": v1 holds a.txt, v2 adds a copy
+/// of it as docs/b.txt and stores the same content again as v2/data/a-again.txt.
+fn create(dir: &Path) -> (PathBuf, String) {
+    let object = dir.join("object");
+    fs::create_dir_all(object.join("v1/data")).unwrap();
+    fs::create_dir_all(object.join("v2/data")).unwrap();
+    fs::write(object.join("0=ocfl_object_1.1"), "ocfl_object_1.1\n").unwrap();
+    fs::write(object.join("v1/data/a.txt"), "alpha").unwrap();
+    fs::write(object.join("v2/data/a-again.txt"), "alpha").unwrap();
+
+    let digest = hex_digest(DigestAlgorithm::Sha512, &b"alpha"[..]).unwrap();
+    let version = |paths: &[&str]| Version {
+        created: String::from("2020-01-01T00:00:00Z"),
+        message: None,
+        user: None,
+        state: DigestMap::from([(
+            digest.clone(),
+            paths.iter().map(|p| p.to_string()).collect(),
+        )]),
+    };
+    let inventory = Inventory {
+        id: String::from("obj"),
+        type_: String::from("https://ocfl.io/1.1/spec/#inventory"),
+        digest_algorithm: String::from("sha512"),
+        head: String::from("v2"),
+        content_directory: Some(String::from("data")),
+        manifest: DigestMap::from([(
+            digest.clone(),
+            vec![
+                String::from("v1/data/a.txt"),
+                String::from("v2/data/a-again.txt"),
+            ],
+        )]),
+        versions: BTreeMap::from([
+            (String::from("v1"), version(&["a.txt"])),
+            (String::from("v2"), version(&["a.txt", "docs/b.txt"])),
+        ]),
+        fixity: BTreeMap::new(),
+    };
+    write_inventory(&object, &inventory).unwrap();
+    (object, digest)
+}
+
+#[test]
+fn maps_between_logical_and_content_paths() {
+    let dir = unique_dir("object_version");
+    let (object, digest) = create(&dir);
+
+    let head = ObjectVersion::open(&object, None).unwrap();
+    assert_eq!(head.name(), "v2");
+    assert_eq!(head.len(), 2);
+    assert_eq!(head.digest("docs/b.txt"), Some(digest.as_str()));
+    assert_eq!(
+        head.resolve("docs/b.txt").unwrap(),
+        ["v1/data/a.txt", "v2/data/a-again.txt"]
+    );
+    assert_eq!(head.resolve("missing.txt"), None);
+    assert_eq!(
+        head.logical_paths_for("v2/data/a-again.txt"),
+        vec!["a.txt", "docs/b.txt"]
+    );
+    // The content directory is "data", not "content".
+    assert!(head.logical_paths_for("v1/content/a.txt").is_empty());
+
+    let files: Vec<_> = head.files().collect();
+    assert_eq!(files, logical_files(&object, None).unwrap());
+    assert_eq!(files[1].logical_path, "docs/b.txt");
+    assert_eq!(files[1].content_path, "v1/data/a.txt");
+    assert_eq!(files[1].size, Some(5));
+
+    let inventory = read_object_inventory(&object).unwrap();
+    let borrowed = ObjectVersion::from_inventory(&object, &inventory, None).unwrap();
+    assert_eq!(borrowed.files().collect::<Vec<_>>(), files);
+
+    let v1 = ObjectVersion::open(&object, Some("v1")).unwrap();
+    assert_eq!(v1.logical_paths_for("v1/data/a.txt"), vec!["a.txt"]);
+    assert_eq!(v1.resolve("docs/b.txt"), None);
+
+    let err = ObjectVersion::open(&object, Some("v3")).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let err = ObjectVersion::open(&dir, None).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,142 @@
+use ocfl_crawler_rust::server::Server;
+use serde_json::Value;
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn start(root: &str) -> String {
+    let server = Server::bind(root, "127.0.0.1:0").expect("failed to bind server");
+    let addr = server.local_addr().expect("server has an IP address");
+    thread::spawn(move || server.run());
+    format!("http://{addr}")
+}
+
+fn get_json(url: &str) -> Value {
+    serde_json::from_str(&ureq::get(url).call().unwrap().into_string().unwrap()).unwrap()
+}
+
+fn status(url: &str) -> u16 {
+    match ureq::get(url).call() {
+        Ok(response) => response.status(),
+        Err(ureq::Error::Status(code, _)) => code,
+        Err(e) => panic!("request failed: {e}"),
+    }
+}
+
+#[test]
+fn lists_objects_with_pagination() {
+    let base = start("tests/roots/0004-hashed-n-tuple");
+
+    let page = get_json(&format!("{base}/objects?limit=1"));
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["objects"].as_array().unwrap().len(), 1);
+
+    let rest = get_json(&format!("{base}/objects?offset=1&limit=10"));
+    assert_eq!(rest["objects"].as_array().unwrap().len(), 1);
+    assert_ne!(page["objects"][0]["id"], rest["objects"][0]["id"]);
+
+    assert_eq!(status(&format!("{base}/objects?limit=x")), 400);
+}
+
+#[test]
+fn serves_object_details_by_identifier() {
+    let base = start("tests/roots/0004-hashed-n-tuple");
+    let object = format!("{base}/objects/ark%3A123%2Fspec-ex-full");
+
+    let info = get_json(&object);
+    assert_eq!(info["id"], "ark:123/spec-ex-full");
+    assert_eq!(info["head"], "v3");
+    assert_eq!(info["spec_version"], "1.1");
+    assert_eq!(info["versions"], serde_json::json!(["v1", "v2", "v3"]));
+    assert!(info["path"].as_str().unwrap().starts_with("565/dbc/475/"));
+
+    let versions = get_json(&format!("{object}/versions"));
+    assert_eq!(versions.as_array().unwrap().len(), 3);
+    assert_eq!(versions[0]["version"], "v1");
+
+    let files = get_json(&format!("{object}/files?version=v1"));
+    let paths: Vec<&str> = files
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["logical_path"].as_str().unwrap())
+        .collect();
+    assert_eq!(paths, vec!["empty.txt", "foo/bar.xml", "image.tiff"]);
+
+    let mut content = String::new();
+    ureq::get(&format!("{object}/content?path=foo%2Fbar.xml&version=v1"))
+        .call()
+        .unwrap()
+        .into_reader()
+        .read_to_string(&mut content)
+        .unwrap();
+    let expected =
+        std::fs::read_to_string("tests/ocfl/1.1/good-objects/spec-ex-full/v1/content/foo/bar.xml")
+            .unwrap();
+    assert_eq!(content, expected);
+}
+
+#[test]
+fn reports_missing_objects_and_routes() {
+    let base = start("tests/roots/0004-hashed-n-tuple");
+    assert_eq!(status(&format!("{base}/objects/no-such-object")), 404);
+    assert_eq!(
+        status(&format!("{base}/objects/ark%3A123%2Fabc/content")),
+        400
+    );
+    assert_eq!(
+        status(&format!(
+            "{base}/objects/ark%3A123%2Fabc/content?path=missing"
+        )),
+        404
+    );
+    assert_eq!(status(&format!("{base}/elsewhere")), 404);
+}
+
+#[test]
+fn locates_objects_without_a_layout_by_crawling() {
+    let base = start("tests/cli/1.1");
+    let info = get_json(&format!("{base}/objects/ark%3A123%2Fabc"));
+    assert_eq!(info["id"], "ark:123/abc");
+}
+
+#[test]
+fn reports_objects_the_crawl_could_not_read() {
+    let dir = unique_dir("server_errors");
+    fs::create_dir_all(dir.join("broken")).unwrap();
+    fs::write(dir.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    fs::write(dir.join("broken/0=ocfl_object_1.1"), "ocfl_object_1.1\n").unwrap();
+    fs::write(dir.join("broken/inventory.json"), "{").unwrap();
+
+    let server = Server::bind(&dir, "127.0.0.1:0").expect("failed to bind server");
+    assert_eq!(server.crawl_errors().len(), 1);
+    let addr = server.local_addr().expect("server has an IP address");
+    thread::spawn(move || server.run());
+
+    let page = get_json(&format!("http://{addr}/objects"));
+    assert_eq!(page["total"], 0);
+    assert_eq!(page["errors"], 1);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,52 @@
+use ocfl_crawler_rust::stats::summarize;
+
+#[test]
+fn summarize_counts_objects_and_bytes() {
+    let summary = summarize("tests/cli/1.0");
+
+    assert_eq!(summary.objects, 2);
+    assert_eq!(summary.spec_versions.get("1.0"), Some(&2));
+    assert_eq!(summary.digest_algorithms.get("sha512"), Some(&2));
+    assert_eq!(summary.version_counts.get(&1), Some(&2));
+    assert_eq!(summary.physical_bytes, 20);
+    assert_eq!(
+        summary.oldest_version.as_deref(),
+        Some("2019-01-01T02:03:04Z")
+    );
+    assert_eq!(summary.errors, 0);
+}
+
+#[test]
+fn summarize_tracks_version_timestamps() {
+    let summary = summarize("tests/ocfl/1.1/good-objects");
+
+    assert_eq!(summary.objects, 2);
+    assert_eq!(summary.digest_algorithms.get("sha256"), Some(&1));
+    assert_eq!(summary.version_counts.get(&3), Some(&1));
+    assert_eq!(
+        summary.oldest_version.as_deref(),
+        Some("2018-01-01T01:01:01Z")
+    );
+    assert_eq!(
+        summary.newest_version.as_deref(),
+        Some("2018-03-03T03:03:03Z")
+    );
+}
+
+#[test]
+fn merging_summaries_adds_storage_roots_together() {
+    let mut summary = summarize("tests/cli/1.0");
+    summary.merge(summarize("tests/ocfl/1.1/good-objects"));
+
+    assert_eq!(summary.objects, 4);
+    assert_eq!(summary.digest_algorithms.get("sha512"), Some(&3));
+    assert_eq!(summary.version_counts.get(&1), Some(&3));
+    assert_eq!(
+        summary.oldest_version.as_deref(),
+        Some("2018-01-01T01:01:01Z")
+    );
+    assert_eq!(
+        summary.newest_version.as_deref(),
+        Some("2019-01-01T02:03:04Z")
+    );
+}
@@ -0,0 +1,93 @@
+use ocfl_crawler_rust::{
+    commit_version,
+    create::VersionInfo,
+    create_object,
+    digest::DigestAlgorithm,
+    usage::{object_usage, storage_root_usage},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    let dir = tmp_root.join(unique);
+    fs::create_dir_all(&dir).expect("failed to create unique temp dir");
+    dir
+}
+
+#[test]
+fn object_usage_splits_new_and_carried_bytes() {
+    let usage = object_usage("tests/ocfl/1.1/good-objects/spec-ex-full").expect("du failed");
+
+    assert_eq!(usage.id, "ark:123/spec-ex-full");
+    assert_eq!(usage.files, 4);
+    assert_eq!(usage.bytes, 48);
+
+    let v3 = usage.versions.last().unwrap();
+    assert_eq!(v3.version, "v3");
+    assert_eq!((v3.new_files, v3.new_bytes), (0, 0));
+    assert_eq!(v3.carried_bytes, v3.logical_bytes);
+    assert_eq!(usage.logical_bytes, v3.logical_bytes);
+}
+
+#[test]
+fn storage_root_usage_totals_objects() {
+    let usage = storage_root_usage("tests/cli/1.1");
+
+    assert!(usage.errors.is_empty());
+    assert_eq!(usage.objects.len(), 2);
+    assert_eq!(usage.files, 1);
+    assert_eq!(usage.bytes, 20);
+}
+
+#[test]
+fn carried_files_only_count_content_from_earlier_versions() {
+    let dir = unique_dir("usage");
+    let root = dir.join("root");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    fs::write(
+        root.join("ocfl_layout.json"),
+        r#"{"extension": "0002-flat-direct-storage-layout", "description": "test"}"#,
+    )
+    .unwrap();
+
+    // v1 holds a.txt; v2 keeps it and adds the same new content as b.txt and c.txt.
+    let source = dir.join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.txt"), "alpha").unwrap();
+    let info = VersionInfo::default();
+    let object = create_object(&root, "obj", &source, DigestAlgorithm::Sha512, &info).unwrap();
+    fs::write(source.join("b.txt"), "bravo").unwrap();
+    fs::write(source.join("c.txt"), "bravo").unwrap();
+    commit_version(&object, &source, &info).unwrap();
+
+    let usage = object_usage(&object).unwrap();
+    let v2 = &usage.versions[1];
+    assert_eq!((v2.new_files, v2.new_bytes), (1, 5));
+    assert_eq!((v2.carried_files, v2.carried_bytes), (1, 5));
+    assert_eq!((v2.logical_files, v2.logical_bytes), (3, 15));
+    assert!(usage.missing.is_empty());
+
+    fs::remove_file(object.join("v1/content/a.txt")).unwrap();
+    let usage = object_usage(&object).unwrap();
+    assert_eq!(usage.missing, ["v1/content/a.txt"]);
+    assert_eq!(usage.bytes, 5);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,103 @@
+#![cfg(target_os = "linux")]
+
+use ocfl_crawler_rust::{
+    index::{ChangeEvent, ChangeKind},
+    watch::Watcher,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+fn unique_dir(prefix: &str) -> PathBuf {
+    let tmp_root = Path::new("tests/tmp");
+    // Ensure tests/tmp exists
+    fs::create_dir_all(tmp_root).expect("failed to create tests/tmp directory");
+
+    // Create a unique subdirectory to avoid test collisions
+    let unique = format!(
+        "{prefix}_{}_{}",
+        std::process::id(),
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time went backwards")
+            .as_nanos()
+    );
+    tmp_root.join(unique)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+fn collect(watcher: &mut Watcher, count: usize) -> Vec<ChangeEvent> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut events = Vec::new();
+    while events.len() < count && Instant::now() < deadline {
+        events.extend(watcher.poll().unwrap());
+        thread::sleep(Duration::from_millis(20));
+    }
+    events
+}
+
+#[test]
+fn reports_new_and_updated_objects() {
+    let root = unique_dir("watch");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("0=ocfl_1.1"), "ocfl_1.1\n").unwrap();
+    copy_dir(
+        Path::new("tests/cli/1.1/minimal_no_content"),
+        &root.join("existing"),
+    );
+
+    let mut watcher = Watcher::new(&root).unwrap();
+
+    // Copy a v1 object into a nested directory, then update it to its v3 state.
+    let object = root.join("ab/cd/spec-ex-full");
+    copy_dir(
+        Path::new("tests/ocfl/1.1/good-objects/spec-ex-full/v1"),
+        &object.join("v1"),
+    );
+    fs::write(object.join("0=ocfl_object_1.1"), "ocfl_object_1.1\n").unwrap();
+    for name in ["inventory.json", "inventory.json.sha512"] {
+        fs::copy(object.join("v1").join(name), object.join(name)).unwrap();
+    }
+
+    let created = collect(&mut watcher, 1);
+    assert_eq!(created.len(), 1, "unexpected events {created:?}");
+    assert_eq!(created[0].kind, ChangeKind::Created);
+    assert_eq!(created[0].id, "ark:123/spec-ex-full");
+    assert_eq!(created[0].head, "v1");
+    assert!(created[0].path.ends_with("ab/cd/spec-ex-full"));
+
+    copy_dir(
+        Path::new("tests/ocfl/1.1/good-objects/spec-ex-full"),
+        &object,
+    );
+
+    let updated = collect(&mut watcher, 1);
+    assert_eq!(updated.len(), 1, "unexpected events {updated:?}");
+    assert_eq!(updated[0].kind, ChangeKind::Updated);
+    assert_eq!(updated[0].head, "v3");
+
+    // The object already present before watching is never reported.
+    assert!(collect(&mut watcher, 1).is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn rejects_paths_that_are_not_storage_roots() {
+    assert!(Watcher::new("tests/cli/1.1/minimal_no_content").is_err());
+}
@@ -0,0 +1,163 @@
+use ocfl_crawler_rust::webhook::{Webhook, parse_header};
+use serde_json::{Value, json};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+/// A request received by the mock server.
+struct Received {
+    headers: Vec<String>,
+    body: Value,
+}
+
+/// Starts a mock HTTP server that answers successive requests with `statuses` and returns its
+/// URL along with a channel of the requests it received.
+fn mock_server(statuses: Vec<u16>) -> (String, Receiver<Received>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut headers = Vec::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_string();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                headers.push(line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            tx.send(Received {
+                headers,
+                body: serde_json::from_slice(&body).unwrap(),
+            })
+            .unwrap();
+        }
+    });
+
+    (url, rx)
+}
+
+#[test]
+fn posts_records_in_batches_with_headers() {
+    let (url, rx) = mock_server(vec![200, 200]);
+    let mut webhook = Webhook::new(&url);
+    webhook.batch_size = 2;
+    webhook
+        .headers
+        .push(parse_header("Authorization: Bearer secret").unwrap());
+
+    for n in 0..3 {
+        webhook.push(json!({ "n": n })).unwrap();
+    }
+    webhook.flush().unwrap();
+
+    let first = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(first.body, json!([{ "n": 0 }, { "n": 1 }]));
+    assert!(
+        first
+            .headers
+            .iter()
+            .any(|h| h == "Authorization: Bearer secret")
+    );
+
+    let second = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(second.body, json!([{ "n": 2 }]));
+}
+
+#[test]
+fn retries_server_errors_with_backoff() {
+    let (url, rx) = mock_server(vec![503, 500, 200]);
+    let mut webhook = Webhook::new(&url);
+    webhook.backoff = Duration::from_millis(10);
+
+    webhook.push(json!({ "path": "a" })).unwrap();
+    webhook.flush().unwrap();
+
+    for _ in 0..3 {
+        let received = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(received.body, json!([{ "path": "a" }]));
+    }
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let (url, rx) = mock_server(vec![400, 200]);
+    let mut webhook = Webhook::new(&url);
+    webhook.backoff = Duration::from_millis(10);
+
+    webhook.push(json!({ "path": "a" })).unwrap();
+    assert!(webhook.flush().is_err());
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn gives_up_after_the_configured_retries() {
+    let (url, _rx) = mock_server(vec![503, 503]);
+    let mut webhook = Webhook::new(&url);
+    webhook.backoff = Duration::from_millis(10);
+    webhook.retries = 1;
+
+    webhook.push(json!({ "path": "a" })).unwrap();
+    assert!(webhook.flush().is_err());
+}
+
+#[test]
+fn waits_out_the_backoff_after_a_failure() {
+    let (url, rx) = mock_server(vec![503, 200]);
+    let mut webhook = Webhook::new(&url);
+    webhook.batch_size = 1;
+    webhook.retries = 0;
+    webhook.backoff = Duration::from_millis(200);
+    webhook.max_pending = 2;
+
+    assert!(webhook.push(json!({ "n": 0 })).is_err());
+    assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+
+    // While paused, records are only queued, and the oldest go once the queue is full.
+    for n in 1..3 {
+        webhook.push(json!({ "n": n })).unwrap();
+    }
+    assert_eq!(
+        webhook.flush().unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+    assert_eq!(webhook.dropped(), 1);
+
+    thread::sleep(Duration::from_millis(200));
+    webhook.flush().unwrap();
+    let received = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(received.body, json!([{ "n": 1 }, { "n": 2 }]));
+}
+
+#[test]
+fn rejects_malformed_headers() {
+    assert_eq!(
+        parse_header("X-Token:  abc ").unwrap(),
+        (String::from("X-Token"), String::from("abc"))
+    );
+    assert!(parse_header("no separator").is_err());
+    assert!(parse_header(": value").is_err());
+}